@@ -7,11 +7,10 @@ import {
     initializeContract,
     mintNfts,
     addToWhiteList,
-    addMetadata,
     retrieveFunds,
     unlockSales,
     updateMintingCost,
-    unlockWhitelist
+    setSalePhase
 } from "./../deploy/call_contract_function.js"
 
 //import 
@@ -38,7 +37,7 @@ yargs(hideBin(process.argv))
         }
     )
     .command(
-        'initialize <ownerAccount> <contractAccount> <mintCost> <royaltiesAccount> <royaltiesValue> <urlMedia> <urlReference>', 'initialize state for deployed contract',
+        'initialize <ownerAccount> <contractAccount> <mintCost> <royaltiesAccount> <royaltiesValue> <maxSupply> <titleTemplate> <description> <seedCommitment> <urlMedia> <urlReference>', 'initialize state for deployed contract',
         (yargs) => {
             yargs.positional(
                 'ownerAccount', {
@@ -70,6 +69,30 @@ yargs(hideBin(process.argv))
                     default: 'Cambi',
                     describe: 'fee to be paid to nft holders from every bet base /100000'
                 });
+            yargs.positional(
+                'maxSupply', {
+                    type: 'string',
+                    default: 'Cambi',
+                    describe: 'total number of NFTs in the collection'
+                });
+            yargs.positional(
+                'titleTemplate', {
+                    type: 'string',
+                    default: 'Cambi',
+                    describe: 'title of each NFT, {id} is replaced by the token id'
+                });
+            yargs.positional(
+                'description', {
+                    type: 'string',
+                    default: 'Cambi',
+                    describe: 'description shared by every NFT in the collection'
+                });
+            yargs.positional(
+                'seedCommitment', {
+                    type: 'string',
+                    default: 'Cambi',
+                    describe: 'base64 sha256 hash of the secret seed revealed after sellout or the end of the sale'
+                });
             yargs.positional(
                 'urlMedia', {
                     type: 'string',
@@ -84,7 +107,7 @@ yargs(hideBin(process.argv))
                 });
         },
         (argv) => {
-            initializeContract(argv.ownerAccount, argv.contractAccount, argv.mintCost, argv.royaltiesAccount, argv.royaltiesValue, argv.urlMedia, argv.urlReference);
+            initializeContract(argv.ownerAccount, argv.contractAccount, argv.mintCost, argv.royaltiesAccount, argv.royaltiesValue, argv.maxSupply, argv.titleTemplate, argv.description, argv.seedCommitment, argv.urlMedia, argv.urlReference);
         }
     )
     .command(
@@ -119,26 +142,6 @@ yargs(hideBin(process.argv))
             addToWhiteList(argv.ownerAccount, argv.contractAccount, argv.listBeneficiaries, argv.allowance);
         }
     )
-    .command(
-        'addMetadata <ownerAccount> <contractAccount>', 'Add metadata for token minting',
-        (yargs) => {
-            yargs.positional(
-                'ownerAccount', {
-                    type: 'string',
-                    default: 'Cambi',
-                    describe: 'account that will send the transaction'
-                });
-            yargs.positional(
-                'contractAccount', {
-                    type: 'string',
-                    default: 'Cambi',
-                    describe: 'account that hosts the contract'
-                });
-        },
-        (argv) => {
-            addMetadata(argv.ownerAccount, argv.contractAccount);
-        }
-    )
     .command(
         'updateMintingCost <ownerAccount> <contractAccount> <newCost>', 'activate emergency panic mode',
         (yargs) => {
@@ -192,7 +195,7 @@ yargs(hideBin(process.argv))
         }
     )
     .command(
-        'unlockWhitelist <ownerAccount> <contractAccount> <status>', 'change locked status of minting',
+        'setSalePhase <ownerAccount> <contractAccount> <name> <start> <end> <price> <perWalletCap> <whitelistOnly>', 'add or replace a phase of the sale schedule',
         (yargs) => {
             yargs.positional(
                 'ownerAccount', {
@@ -207,14 +210,44 @@ yargs(hideBin(process.argv))
                     describe: 'account that hosts the contract'
                 });
             yargs.positional(
-                'status', {
+                'name', {
                     type: 'string',
                     default: 'Cambi',
-                    describe: 'new locked status for contract'
+                    describe: 'name of the phase, an existing phase with this name is replaced'
+                });
+            yargs.positional(
+                'start', {
+                    type: 'string',
+                    default: 'Cambi',
+                    describe: 'start date of the phase, v.g. 2022-05-01T18:00:00Z'
+                });
+            yargs.positional(
+                'end', {
+                    type: 'string',
+                    default: 'Cambi',
+                    describe: 'end date of the phase, v.g. 2022-05-02T18:00:00Z'
+                });
+            yargs.positional(
+                'price', {
+                    type: 'string',
+                    default: 'Cambi',
+                    describe: 'price in NEAR to mint a NFT in this phase, use default for the mint cost'
+                });
+            yargs.positional(
+                'perWalletCap', {
+                    type: 'string',
+                    default: 'Cambi',
+                    describe: 'max NFTs minted per wallet in this phase, use none for no cap'
+                });
+            yargs.positional(
+                'whitelistOnly', {
+                    type: 'string',
+                    default: 'Cambi',
+                    describe: 'true if only whitelisted wallets can mint in this phase'
                 });
         },
         (argv) => {
-            unlockWhitelist(argv.ownerAccount, argv.contractAccount, argv.status);
+            setSalePhase(argv.ownerAccount, argv.contractAccount, argv.name, argv.start, argv.end, argv.price, argv.perWalletCap, argv.whitelistOnly);
         }
     )
     .command(
@@ -11,8 +11,8 @@ async function buildContractObject(ownerAccount, contractAccount) {
             viewMethods: [],
             changeMethods: [
                 "new", "nft_mint", "nft_burn", "mint",
-                "add_to_whitelist", "initilize_random_generator", "retrieve_funds",
-                "unlock_sales", "unlock_whitelist", "change_mint_cost"
+                "add_to_whitelist", "retrieve_funds",
+                "unlock_sales", "set_sale_phase", "change_mint_cost"
             ],
             sender: account, // account object to initialize and sign transactions.
         }
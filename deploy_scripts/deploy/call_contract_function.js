@@ -5,7 +5,7 @@ import fs from "fs";
 import buildContractObject from "./_contract_object.js";
 import { BN } from "bn.js";
 
-async function initializeContract(ownerAccount, contractAccount, mint_cost, royalties_account, royalties_value, urlMedia, urlReference) {
+async function initializeContract(ownerAccount, contractAccount, mint_cost, royalties_account, royalties_value, maxSupply, titleTemplate, description, seedCommitment, urlMedia, urlReference) {
     const contract = await buildContractObject(ownerAccount, contractAccount);
 
     let namedArgs = {
@@ -22,6 +22,10 @@ async function initializeContract(ownerAccount, contractAccount, mint_cost, roya
         mint_cost: nearAPI.utils.format.parseNearAmount(mint_cost),
         royalties_account: royalties_account,
         royalties_value: royalties_value,
+        max_supply: parseInt(maxSupply),
+        title_template: titleTemplate,
+        description: description,
+        seed_commitment: seedCommitment,
         url_media_base: urlMedia,
         url_reference_base: urlReference
 
@@ -85,39 +89,25 @@ async function addToWhiteList(ownerAccount, contractAccount, listBeneficiaries,
     const contract = await buildContractObject(ownerAccount, contractAccount);
 
     let whiteListMap = {};
-    for (let item of JSON.parse(listBeneficiaries)) {
+    const beneficiaries = JSON.parse(listBeneficiaries);
+    for (let item of beneficiaries) {
         console.log(item);
         whiteListMap[item] = parseInt(allowance)
     }
 
-
+    //covers the storage of the new entries, the surplus is refunded
+    const storageDeposit = nearAPI.utils.format.parseNearAmount((0.005 * beneficiaries.length).toFixed(3));
     const result = await contract.add_to_whitelist({
             whitelist_map: whiteListMap
         },
         "300000000000000",
-        "1"
+        storageDeposit
     );
 
     console.log(result);
     return result;
 }
 
-//add metadata to contract
-async function addMetadata(ownerAccount, contractAccount) {
-    const contract = await buildContractObject(ownerAccount, contractAccount);
-
-    let result = false;
-    while (!result) {
-        console.log(result);
-        result = await contract.initilize_random_generator({},
-            "300000000000000",
-            "0"
-        )
-    }
-
-    console.log(result);
-}
-
 //update_contract
 async function retrieveFunds(ownerAccount, contractAccount, quantity) {
     const contract = await buildContractObject(ownerAccount, contractAccount);
@@ -148,12 +138,22 @@ async function unlockSales(ownerAccount, contractAccount, status) {
     return result;
 }
 
-//lock/unlock whitelist
-async function unlockWhitelist(ownerAccount, contractAccount, status) {
+//add or replace a sale phase
+async function setSalePhase(ownerAccount, contractAccount, name, start, end, price, perWalletCap, whitelistOnly) {
     const contract = await buildContractObject(ownerAccount, contractAccount);
 
-    const result = await contract.unlock_whitelist({
-            whitelist_lock: (status === "true")
+    //block timestamps are in nanoseconds
+    const toNanoseconds = (date) => new BN(Date.parse(date)).mul(new BN("1000000")).toString(10);
+
+    const result = await contract.set_sale_phase({
+            phase: {
+                name: name,
+                start: toNanoseconds(start),
+                end: toNanoseconds(end),
+                price: price === "default" ? null : nearAPI.utils.format.parseNearAmount(price.toString()),
+                per_wallet_cap: perWalletCap === "none" ? null : parseInt(perWalletCap),
+                whitelist_only: (whitelistOnly === "true")
+            }
         },
         "300000000000000",
         "1"
@@ -182,9 +182,8 @@ export {
     initializeContract,
     mintNfts,
     addToWhiteList,
-    addMetadata,
     retrieveFunds,
     unlockSales,
     updateMintingCost,
-    unlockWhitelist
+    setSalePhase
 };
\ No newline at end of file
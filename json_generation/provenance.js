@@ -0,0 +1,15 @@
+import fs from "fs";
+import crypto from "crypto";
+
+//provenance hash of the collection: sha256 over the reference_hash of every token, in id order
+let rawdata = fs.readFileSync('./json/_metadata.json');
+let allData = JSON.parse(rawdata);
+
+let concatenatedHashes = "";
+for (let counter = 1; counter <= allData.length; counter++) {
+    let chainData = JSON.parse(fs.readFileSync(`./chain_json/${counter}.json`));
+    concatenatedHashes += chainData.reference_hash;
+}
+
+let provenanceHash = crypto.createHash('sha256').update(concatenatedHashes).digest('hex');
+console.log(provenanceHash);
@@ -0,0 +1,52 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::utils::refund_deposit;
+use near_sdk::Gas;
+
+/// Gas kept aside to mint one more airdropped token and save the state.
+const GAS_FOR_AIRDROP_MINT: Gas = 20_000_000_000_000;
+/// Tokens airdropped per call, each logs a nft_mint and a token_draw event and a receipt
+/// can't log more than 100 lines.
+const MAX_AIRDROP_PER_CALL: u32 = 45;
+
+/// Outcome of an airdrop call, `remaining` is what still has to be sent in a later call.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AirdropResult {
+    pub minted: u32,
+    pub remaining: Vec<(AccountId, u32)>,
+}
+
+#[near_bindgen]
+impl Contract {
+    //mints tokens from the random pool to each account without charging the mint cost.
+    //the owner only pays the storage, the list is processed until the gas or the page runs out
+    #[payable]
+    pub fn airdrop(&mut self, drops: Vec<(ValidAccountId, u32)>) -> AirdropResult {
+        let owner_id = env::predecessor_account_id();
+        require(owner_id == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+
+        let initial_storage_usage = env::storage_usage();
+        let mut minted: u32 = 0;
+        let mut remaining = Vec::new();
+        for (account_id, quantity) in drops {
+            let account_id: AccountId = account_id.into();
+            let mut left = quantity;
+            while left > 0
+                && self.pool_remaining > 0
+                && minted < MAX_AIRDROP_PER_CALL
+                && env::prepaid_gas() - env::used_gas() > GAS_FOR_AIRDROP_MINT
+            {
+                self.internal_mint_random(&owner_id, &account_id, 1, 0, Some("airdrop".to_string()));
+                left -= 1;
+                minted += 1;
+            }
+            if left > 0 {
+                remaining.push((account_id, left));
+            }
+        }
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+        AirdropResult { minted, remaining }
+    }
+}
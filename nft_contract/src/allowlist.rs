@@ -0,0 +1,52 @@
+use crate::*;
+use crate::merkle::{leaf_hash, verify, HASH_LEN};
+
+/// Leaf of the merkle allowlist proven by a minter, see `merkle.rs` for the hashing.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AllowlistProof {
+    pub allowance: u32,
+    pub proof: Vec<Base64VecU8>,
+}
+
+#[near_bindgen]
+impl Contract {
+    //whitelist only phases also accept mints proven against this root, None disables them.
+    //amounts already claimed are kept when the root changes
+    #[payable]
+    pub fn set_allowlist_root(&mut self, root: Option<Base64VecU8>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        if let Some(root) = &root {
+            require(root.0.len() == HASH_LEN, ContractError::InvalidAllowlistRoot);
+        }
+
+        self.allowlist_root = root.map(|root| root.0);
+        true
+    }
+
+    pub fn allowlist_root(&self) -> Option<Base64VecU8> {
+        self.allowlist_root.clone().map(Base64VecU8)
+    }
+
+    //number of NFTs account_id minted through the allowlist
+    pub fn allowlist_claimed(&self, account_id: ValidAccountId) -> u32 {
+        self.allowlist_claimed.get(account_id.as_ref()).unwrap_or(0)
+    }
+}
+
+impl Contract {
+    //checks the proof of account_id and debits quantity from its allowance
+    pub(crate) fn debit_allowlist(&mut self, account_id: &AccountId, allowlist: &AllowlistProof, quantity: u128) {
+        let root = self.allowlist_root.as_ref().unwrap_or_else(|| ContractError::NoAllowlist.panic());
+        let proof: Vec<Vec<u8>> = allowlist.proof.iter().map(|hash| hash.0.clone()).collect();
+        require(
+            verify(root, leaf_hash(account_id, allowlist.allowance), &proof),
+            ContractError::InvalidAllowlistProof
+        );
+
+        let claimed = u128::from(self.allowlist_claimed.get(account_id).unwrap_or(0));
+        require(claimed + quantity <= u128::from(allowlist.allowance), ContractError::WhitelistAllowanceExceeded);
+        self.allowlist_claimed.insert(account_id, &((claimed + quantity) as u32));
+    }
+}
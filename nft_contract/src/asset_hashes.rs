@@ -0,0 +1,44 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::utils::refund_deposit;
+
+/// sha256 hashes of the media and reference json of one collection index.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AssetHashes {
+    pub media_hash: Base64VecU8,
+    pub reference_hash: Base64VecU8,
+}
+
+#[near_bindgen]
+impl Contract {
+    //uploads the hashes keyed by collection index, the attached deposit pays for their storage
+    #[payable]
+    pub fn add_asset_hashes(&mut self, asset_hashes: HashMap<TokenId, AssetHashes>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(!self.sale_started(), ContractError::SaleAlreadyStarted);
+
+        let initial_storage_usage = env::storage_usage();
+        for (key, hashes) in asset_hashes.iter() {
+            let index: u64 = key.parse().unwrap_or_else(|_| ContractError::InvalidCollectionIndex.panic());
+            require(index >= 1 && index <= self.max_supply, ContractError::IndexOutOfRange);
+            require(
+                hashes.media_hash.0.len() == 32 && hashes.reference_hash.0.len() == 32,
+                ContractError::InvalidAssetHash
+            );
+            if self.asset_hashes.insert(&index, hashes).is_none() {
+                self.asset_hashes_count += 1;
+            }
+        }
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+        true
+    }
+
+    pub fn asset_hashes(&self, index: u64) -> Option<AssetHashes> {
+        self.asset_hashes.get(&index)
+    }
+
+    pub fn asset_hashes_count(&self) -> u64 {
+        self.asset_hashes_count
+    }
+}
@@ -0,0 +1,219 @@
+use crate::*;
+use near_sdk::json_types::{U128, U64};
+
+/// Dutch auction pricing of the public phases. From `start` the price drops by `decrement`
+/// every `step_interval` nanoseconds, down to `floor_price`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DutchAuction {
+    pub start: U64,
+    pub start_price: U128,
+    pub floor_price: U128,
+    pub decrement: U128,
+    pub step_interval: U64,
+    // buyers can claim back what they paid above the final clearing price
+    pub rebate: bool,
+}
+
+impl DutchAuction {
+    pub fn price_at(&self, now: u64) -> u128 {
+        if now <= self.start.0 {
+            return self.start_price.0;
+        }
+        let steps = u128::from((now - self.start.0) / self.step_interval.0);
+        let price = self.start_price.0.saturating_sub(steps.saturating_mul(self.decrement.0));
+        std::cmp::max(price, self.floor_price.0)
+    }
+}
+
+/// What an account paid in the auction, reduced by the rebates it already claimed.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuctionPurchase {
+    pub quantity: u32,
+    pub paid: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    //sets the auction used by the public phases, None goes back to fixed prices
+    #[payable]
+    pub fn set_dutch_auction(&mut self, auction: Option<DutchAuction>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(self.auction_last_price.is_none(), ContractError::SaleAlreadyStarted);
+        if let Some(auction) = &auction {
+            require(
+                auction.start_price.0 >= auction.floor_price.0,
+                ContractError::InvalidAuction("start price must be above the floor price")
+            );
+            require(auction.step_interval.0 > 0, ContractError::InvalidAuction("step interval must be greater than 0"));
+        }
+
+        self.dutch_auction = auction;
+        true
+    }
+
+    pub fn dutch_auction(&self) -> Option<DutchAuction> {
+        self.dutch_auction.clone()
+    }
+
+    //price of one NFT if minted now, in the active phase
+    pub fn current_price(&self) -> U128 {
+        let now = env::block_timestamp();
+        let price = match self.current_phase() {
+            Some(phase) => self.phase_price(&phase, now),
+            None => match &self.dutch_auction {
+                Some(auction) => auction.price_at(now),
+                None => self.mint_cost,
+            },
+        };
+        U128(price)
+    }
+
+    pub fn auction_purchase(&self, account_id: ValidAccountId) -> Option<AuctionPurchase> {
+        self.auction_purchases.get(account_id.as_ref())
+    }
+
+    //pays back what the caller paid above the clearing price, once the auction is settled
+    pub fn claim_rebate(&mut self) -> U128 {
+        let auction = self.dutch_auction.as_ref().unwrap_or_else(|| ContractError::NoAuction.panic());
+        require(auction.rebate, ContractError::NoRebates);
+        require(self.auction_settled(), ContractError::AuctionNotSettled);
+
+        let account_id = env::predecessor_account_id();
+        let mut purchase = self.auction_purchases.get(&account_id)
+            .unwrap_or_else(|| ContractError::NoAuctionPurchase.panic());
+        let clearing_price = self.auction_last_price.unwrap_or_else(|| ContractError::AuctionNotSettled.panic());
+        let due = clearing_price * u128::from(purchase.quantity);
+        let rebate = purchase.paid.0.saturating_sub(due);
+        if rebate > 0 {
+            purchase.paid = U128(due);
+            self.auction_purchases.insert(&account_id, &purchase);
+            self.auction_paid -= rebate;
+            self.revenue_accrued -= rebate;
+            Promise::new(account_id).transfer(rebate);
+        }
+        U128(rebate)
+    }
+}
+
+impl Contract {
+    //price of one NFT in `phase`, public phases follow the auction when there is one
+    pub(crate) fn phase_price(&self, phase: &SalePhase, now: u64) -> u128 {
+        match (&self.dutch_auction, phase.whitelist_only) {
+            (Some(auction), false) => auction.price_at(now),
+            _ => phase.price.map(|price| price.0).unwrap_or(self.mint_cost),
+        }
+    }
+
+    //keeps what was paid in the auction, the last price is the clearing price candidate
+    pub(crate) fn record_auction_purchase(&mut self, account_id: &AccountId, quantity: u32, price: u128) {
+        self.auction_last_price = Some(price);
+        let rebate = self.dutch_auction.as_ref().map(|auction| auction.rebate).unwrap_or(false);
+        if rebate {
+            let mut purchase = self.auction_purchases.get(account_id)
+                .unwrap_or(AuctionPurchase { quantity: 0, paid: U128(0) });
+            purchase.quantity += quantity;
+            purchase.paid = U128(purchase.paid.0 + price * u128::from(quantity));
+            self.auction_purchases.insert(account_id, &purchase);
+            self.auction_paid += price * u128::from(quantity);
+            self.auction_quantity += u64::from(quantity);
+        }
+    }
+
+    //the clearing price is final: nothing is left to sell, or a sale already happened at the floor.
+    //a current price at the floor is not enough, the last sale may still be above it
+    pub(crate) fn auction_settled(&self) -> bool {
+        match (&self.dutch_auction, self.auction_last_price) {
+            (Some(auction), Some(last_price)) => self.sold_out() || last_price == auction.floor_price.0,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::test_utils::{new_contract, owner_context};
+    use near_sdk::test_utils::accounts;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    //100 at 100ns, then 20 less every 10ns down to 40 at 130ns
+    fn auction() -> DutchAuction {
+        DutchAuction {
+            start: U64(100),
+            start_price: U128(100),
+            floor_price: U128(40),
+            decrement: U128(20),
+            step_interval: U64(10),
+            rebate: true,
+        }
+    }
+
+    #[test]
+    fn price_steps_down_to_the_floor() {
+        let auction = auction();
+        assert_eq!(auction.price_at(0), 100);
+        assert_eq!(auction.price_at(100), 100);
+        assert_eq!(auction.price_at(109), 100);
+        assert_eq!(auction.price_at(110), 80);
+        assert_eq!(auction.price_at(129), 60);
+        assert_eq!(auction.price_at(130), 40);
+        assert_eq!(auction.price_at(140), 40);
+    }
+
+    #[test]
+    fn overflowing_decrement_saturates_to_the_floor() {
+        let auction = DutchAuction { decrement: U128(u128::MAX), ..auction() };
+        assert_eq!(auction.price_at(109), 100);
+        assert_eq!(auction.price_at(110), 40);
+        assert_eq!(auction.price_at(u64::MAX), 40);
+    }
+
+    //two buyers above the floor, then 130ns: the current price is at the floor but no sale was
+    fn auction_above_the_floor() -> Contract {
+        let mut contract = new_contract(10);
+        contract.set_dutch_auction(Some(auction()));
+        contract.record_auction_purchase(&accounts(3).to_string(), 2, 100);
+        contract.accrue_revenue(200);
+        contract.record_auction_purchase(&accounts(4).to_string(), 1, 80);
+        contract.accrue_revenue(80);
+        testing_env!(owner_context().block_timestamp(130).build());
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "E503")]
+    fn floor_price_alone_does_not_settle() {
+        let mut contract = auction_above_the_floor();
+        assert!(!contract.auction_settled());
+        testing_env!(owner_context().predecessor_account_id(accounts(3)).block_timestamp(130).build());
+        contract.claim_rebate();
+    }
+
+    #[test]
+    fn withdrawals_keep_the_rebates_until_a_sale_at_the_floor() {
+        let mut contract = auction_above_the_floor();
+        //rebates are counted at the floor price until the auction settles
+        assert_eq!(contract.revenue_ledger().rebate_liability.0, 160);
+        contract.retrieve_funds(U128(u128::MAX));
+        assert_eq!(contract.revenue_ledger().withdrawn.0, 120);
+
+        contract.record_auction_purchase(&accounts(5).to_string(), 1, 40);
+        contract.accrue_revenue(40);
+        assert!(contract.auction_settled());
+        assert_eq!(contract.revenue_ledger().rebate_liability.0, 160);
+
+        testing_env!(owner_context().predecessor_account_id(accounts(3)).block_timestamp(140).build());
+        assert_eq!(contract.claim_rebate().0, 120);
+        assert_eq!(contract.claim_rebate().0, 0);
+        testing_env!(owner_context().predecessor_account_id(accounts(4)).block_timestamp(140).build());
+        assert_eq!(contract.claim_rebate().0, 40);
+
+        let ledger = contract.revenue_ledger();
+        assert_eq!(ledger.accrued.0, 160);
+        assert_eq!(ledger.rebate_liability.0, 0);
+        assert_eq!(ledger.withdrawable.0, 40);
+    }
+}
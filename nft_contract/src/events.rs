@@ -0,0 +1,91 @@
+//! Contract specific events, logged in the nep-297 format under the `tokonami` standard.
+//!
+//! Token ownership changes are still reported through the nep171 events of the standard
+//! crate, these only cover the bookkeeping that is particular to this contract.
+
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::serde::Serialize;
+use near_sdk::{log, serde_json, AccountId};
+
+/// Inputs and results of the draws of one mint call, logged once per call to stay within the
+/// log limits of a receipt. Draw `k` of the batch used the nonce `first_nonce + k`, the batch
+/// index `k` and a pool of `pool_len - k` ids. To log this event,
+/// call [`.emit()`](TokenDraw::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenDraw<'a> {
+    pub minter: &'a AccountId,
+    pub block_seed: Base64VecU8,
+    pub first_nonce: u64,
+    pub pool_len: u64,
+    pub token_ids: &'a [u64],
+    pub pool_indexes: &'a [u64],
+}
+
+impl TokenDraw<'_> {
+    pub fn emit(self) {
+        new_tokonami_v1(TokonamiEventKind::TokenDraw(&[self])).emit()
+    }
+}
+
+/// The owner published the seed matching the commitment given at initialization.
+/// `starting_index` is the metadata offset it fixed, mixed with the randomness of that block.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeedReveal {
+    pub seed: Base64VecU8,
+    pub starting_index: Option<u64>,
+}
+
+impl SeedReveal {
+    pub fn emit(self) {
+        new_tokonami_v1(TokonamiEventKind::SeedReveal(&[self])).emit()
+    }
+}
+
+/// Mint revenue sent to the funds beneficiary, `total_withdrawn` includes this withdrawal.
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FundsWithdrawn<'a> {
+    pub beneficiary: &'a AccountId,
+    pub amount: U128,
+    pub total_withdrawn: U128,
+}
+
+impl FundsWithdrawn<'_> {
+    pub fn emit(self) {
+        new_tokonami_v1(TokonamiEventKind::FundsWithdrawn(&[self])).emit()
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+struct TokonamiEvent<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event_kind: TokonamiEventKind<'a>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+enum TokonamiEventKind<'a> {
+    TokenDraw(&'a [TokenDraw<'a>]),
+    SeedReveal(&'a [SeedReveal]),
+    FundsWithdrawn(&'a [FundsWithdrawn<'a>]),
+}
+
+impl TokonamiEvent<'_> {
+    fn emit(self) {
+        log!("EVENT_JSON:{}", serde_json::to_string(&self).unwrap());
+    }
+}
+
+fn new_tokonami_v1(event_kind: TokonamiEventKind) -> TokonamiEvent {
+    TokonamiEvent { standard: "tokonami", version: "1.0.0", event_kind }
+}
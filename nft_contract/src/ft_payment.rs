@@ -0,0 +1,181 @@
+use crate::*;
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+use near_contract_standards::non_fungible_token::utils::assert_one_yocto;
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, serde_json, Balance, Gas, PromiseResult};
+
+const GAS_FOR_FT_TRANSFER: Gas = 10_000_000_000_000;
+const GAS_FOR_RESOLVE_FT_FUNDS: Gas = 5_000_000_000_000;
+
+#[ext_contract(ext_ft)]
+trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+#[ext_contract(ext_self)]
+trait FtFundsResolver {
+    fn resolve_ft_funds(&mut self, ft_contract: AccountId, amount: U128) -> bool;
+}
+
+/// Expected `msg` of the `ft_transfer_call` paying for a mint, v.g. `{"quantity": 3}`.
+/// `receiver_id` mints to another account than the sender, `allowlist` works as in `nft_mint`.
+/// Mint lists have their own NEAR price, so `list` is rejected here and must go through `nft_mint`.
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FtMintArgs {
+    pub quantity: u32,
+    #[serde(default)]
+    pub receiver_id: Option<ValidAccountId>,
+    #[serde(default)]
+    pub allowlist: Option<AllowlistProof>,
+    #[serde(default)]
+    pub list: Option<String>,
+}
+
+#[near_bindgen]
+impl Contract {
+    //accepts ft_contract as payment at `price` per NFT, None stops accepting it
+    #[payable]
+    pub fn set_ft_price(&mut self, ft_contract: ValidAccountId, price: Option<U128>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+
+        match price {
+            Some(price) => self.ft_prices.insert(ft_contract.as_ref(), &price),
+            None => self.ft_prices.remove(ft_contract.as_ref()),
+        };
+        true
+    }
+
+    pub fn ft_prices(&self) -> Vec<(AccountId, U128)> {
+        self.ft_prices.to_vec()
+    }
+
+    //sends up to `amount` of the ft_contract tokens earned by the sale to the funds beneficiary,
+    //which has to be registered with the token contract. A failed transfer is booked back
+    #[payable]
+    pub fn retrieve_ft_funds(&mut self, ft_contract: ValidAccountId, amount: U128) -> Promise {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        let ft_contract: AccountId = ft_contract.into();
+        let available = self.ft_revenue.get(&ft_contract).unwrap_or(0);
+        let amount = std::cmp::min(amount.0, available);
+        require(amount > 0, ContractError::NothingToWithdraw);
+
+        self.ft_revenue.insert(&ft_contract, &(available - amount));
+        ext_ft::ft_transfer(
+            self.funds_beneficiary.clone(),
+            U128(amount),
+            Some("mint revenue".to_string()),
+            &ft_contract,
+            1,
+            GAS_FOR_FT_TRANSFER,
+        )
+        .then(ext_self::resolve_ft_funds(
+            ft_contract,
+            U128(amount),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_FT_FUNDS,
+        ))
+    }
+
+    #[private]
+    pub fn resolve_ft_funds(&mut self, ft_contract: AccountId, amount: U128) -> bool {
+        let transferred = matches!(env::promise_result(0), PromiseResult::Successful(_));
+        if !transferred {
+            let available = self.ft_revenue.get(&ft_contract).unwrap_or(0);
+            self.ft_revenue.insert(&ft_contract, &(available + amount.0));
+        }
+        transferred
+    }
+
+    //ft_contract tokens earned by the sale and not retrieved yet
+    pub fn ft_revenue(&self, ft_contract: ValidAccountId) -> U128 {
+        U128(self.ft_revenue.get(ft_contract.as_ref()).unwrap_or(0))
+    }
+
+    //NEAR paying the storage of the NFTs account_id mints with fungible tokens,
+    //the storage of the deposit itself is taken from it
+    #[payable]
+    pub fn ft_mint_storage_deposit(&mut self, account_id: Option<ValidAccountId>) -> U128 {
+        let account_id: AccountId = account_id.map(|account_id| account_id.into()).unwrap_or_else(env::predecessor_account_id);
+        let deposit = env::attached_deposit();
+        require(deposit > 0, ContractError::RequiresAttachedDeposit);
+
+        let initial_storage_usage = env::storage_usage();
+        let balance = self.ft_storage_deposits.get(&account_id).unwrap_or(0) + deposit;
+        self.ft_storage_deposits.insert(&account_id, &balance);
+        let entry_cost = Balance::from(env::storage_usage().saturating_sub(initial_storage_usage)) * env::storage_byte_cost();
+        require(balance > entry_cost, ContractError::InsufficientDeposit { required: entry_cost });
+
+        self.ft_storage_deposits.insert(&account_id, &(balance - entry_cost));
+        self.ft_storage_deposited += deposit - entry_cost;
+        U128(balance - entry_cost)
+    }
+
+    pub fn ft_mint_storage_balance(&self, account_id: ValidAccountId) -> U128 {
+        U128(self.ft_storage_deposits.get(account_id.as_ref()).unwrap_or(0))
+    }
+
+    //sends back what is left of the storage deposit of the caller
+    #[payable]
+    pub fn ft_mint_storage_withdraw(&mut self) -> Promise {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let balance = self.ft_storage_deposits.remove(&account_id).unwrap_or(0);
+        require(balance > 0, ContractError::NothingToWithdraw);
+
+        self.ft_storage_deposited -= balance;
+        Promise::new(account_id).transfer(balance)
+    }
+}
+
+impl Contract {
+    //takes the storage of a fungible token mint from the storage deposit of account_id
+    fn charge_ft_storage(&mut self, account_id: &AccountId, storage_used: u64) {
+        let required = Balance::from(storage_used) * env::storage_byte_cost();
+        let balance = self.ft_storage_deposits.get(account_id).unwrap_or(0);
+        require(balance >= required, ContractError::InsufficientFtStorageDeposit { required });
+
+        self.ft_storage_deposits.insert(account_id, &(balance - required));
+        self.ft_storage_deposited -= required;
+    }
+}
+
+/// Mints paid in fungible tokens follow the same phases, whitelist and caps as the NEAR ones.
+/// No NEAR comes with `ft_on_transfer`, so the storage of these tokens is taken from the
+/// `ft_mint_storage_deposit` of the sender, the call fails and the tokens go back when it's short.
+/// When fewer NFTs are left than requested, the tokens for the unfilled part are sent back.
+#[near_bindgen]
+impl FungibleTokenReceiver for Contract {
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: ValidAccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let ft_contract = env::predecessor_account_id();
+        let price = self.ft_prices.get(&ft_contract).unwrap_or_else(|| ContractError::FtNotAccepted.panic());
+        let args: FtMintArgs = serde_json::from_str(&msg).unwrap_or_else(|_| ContractError::InvalidFtMsg.panic());
+        let account_id: AccountId = sender_id.into();
+        let receiver_id: AccountId = args.receiver_id.map(|receiver_id| receiver_id.into()).unwrap_or_else(|| account_id.clone());
+        let quantity = u128::from(args.quantity);
+        require(amount.0 >= price.0 * quantity, ContractError::InsufficientFtAmount { required: price.0 * quantity });
+
+        require(args.list.is_none(), ContractError::FtMintListNotSupported);
+        require(!self.sales_locked, ContractError::SalesLocked);
+        let filled = self.fillable(quantity);
+        let cost = price.0 * filled;
+        self.assert_phase_allows_mint(&account_id, filled, args.allowlist.as_ref());
+        let initial_storage_usage = env::storage_usage();
+        let memo = if receiver_id != account_id { Some(format!("paid by {}", account_id)) } else { None };
+        self.internal_mint_random(&account_id, &receiver_id, filled, 0, memo);
+        self.record_minted(&account_id, filled as u32);
+        let earned = self.ft_revenue.get(&ft_contract).unwrap_or(0);
+        self.ft_revenue.insert(&ft_contract, &(earned + cost));
+        self.charge_ft_storage(&account_id, env::storage_usage().saturating_sub(initial_storage_usage));
+
+        PromiseOrValue::Value(U128(amount.0 - cost))
+    }
+}
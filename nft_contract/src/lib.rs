@@ -15,14 +15,16 @@ NOTES:
   - To prevent the deployed contract from being modified or deleted, it should not have any access
     keys on its account.
 */
+use near_contract_standards::error::{require, ContractError};
 use near_contract_standards::non_fungible_token::metadata::{
     NFTContractMetadata, NonFungibleTokenMetadataProvider, TokenMetadata, NFT_METADATA_SPEC,
 };
 use near_contract_standards::non_fungible_token::{Token, TokenId};
 use near_contract_standards::non_fungible_token::NonFungibleToken;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LazyOption, LookupMap, Vector};
-use near_sdk::json_types::{ValidAccountId};
+use near_sdk::collections::{LazyOption, LookupMap, LookupSet, UnorderedMap, UnorderedSet};
+use near_sdk::json_types::{Base64VecU8, ValidAccountId};
+use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
     env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue,
 };
@@ -33,10 +35,38 @@ use near_contract_standards::non_fungible_token::royalty::{Royalty, Payout};
 use near_contract_standards::non_fungible_token::events::{NftBurn};
 use std::convert::TryInto;
 
+use crate::allowlist::AllowlistProof;
+use crate::asset_hashes::AssetHashes;
+use crate::auction::{AuctionPurchase, DutchAuction};
+use crate::events::{SeedReveal, TokenDraw};
+use crate::mint_lists::MintList;
+use crate::randomness::{draw_index, matches_commitment, SEED_LEN};
+use crate::sale::{PublicMintLimits, SalePhase};
+
+mod airdrop;
+mod allowlist;
+mod asset_hashes;
+mod auction;
+mod events;
+mod ft_payment;
+pub mod merkle;
+mod mint_lists;
+mod provenance;
+pub mod randomness;
+mod reserved;
+mod revenue;
+mod reveal;
+mod royalties;
+mod sale;
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test_utils;
+pub mod vouchers;
+mod whitelist;
+
 near_sdk::setup_alloc!();
 
 pub fn assert_one_or_more_yocto() {
-    assert!(env::attached_deposit() >= 1, "Requires attached deposit of 1 yoctoNEAR or more")
+    require(env::attached_deposit() >= 1, ContractError::RequiresAttachedDeposit)
 }
 
 #[near_bindgen]
@@ -46,17 +76,96 @@ pub struct Contract {
     pub metadata: LazyOption<NFTContractMetadata>,
 
     pub funds_beneficiary: AccountId,
-    pub perpetual_royalties: HashMap<AccountId, u128>,
-    pub whitelist: LookupMap<AccountId, u128>,
+    pub revenue_accrued: u128,
+    pub revenue_withdrawn: u128,
+    pub revenue_splits: HashMap<AccountId, u32>,
+    pub revenue_distributed: LookupMap<AccountId, u128>,
+    // next collection id whose own royalty is dropped for the default one, 0 when no migration was asked
+    pub royalty_migration_cursor: u64,
+    // royalty of the older versions, only the per token copies equal to it are dropped by the migration
+    pub royalty_migration_from: HashMap<AccountId, u128>,
+    pub whitelist: UnorderedMap<AccountId, u128>,
+    pub allowlist_root: Option<Vec<u8>>,
+    pub allowlist_claimed: LookupMap<AccountId, u32>,
+    pub mint_lists: UnorderedMap<String, MintList>,
+    pub mint_list_members: LookupSet<(String, AccountId)>,
+    pub mint_list_consumed: LookupMap<(String, AccountId), u32>,
+    pub voucher_key: Option<Vec<u8>>,
+    pub used_voucher_nonces: LookupSet<u64>,
     pub mint_cost: u128,
     pub sales_locked: bool,
-    pub only_whitelist: bool,
-    pub random_minting: Vector<u128>,
+    pub sale_phases: Vec<SalePhase>,
+    pub phase_minted: LookupMap<(String, AccountId), u32>,
+    pub public_mint_limits: PublicMintLimits,
+    pub minted_by_account: LookupMap<AccountId, u32>,
+    pub dutch_auction: Option<DutchAuction>,
+    pub auction_last_price: Option<u128>,
+    pub auction_purchases: LookupMap<AccountId, AuctionPurchase>,
+    pub auction_paid: u128,
+    pub auction_quantity: u64,
+    pub ft_prices: UnorderedMap<AccountId, U128>,
+    // fungible tokens earned per token contract and not retrieved yet
+    pub ft_revenue: LookupMap<AccountId, u128>,
+    // NEAR deposited by fungible token buyers to pay the storage of their NFTs
+    pub ft_storage_deposits: LookupMap<AccountId, u128>,
+    pub ft_storage_deposited: u128,
+    // random pool as a virtual array of the ids left, slot i holds id i + 1 unless swapped
+    pub pool_swaps: LookupMap<u64, u64>,
+    pub pool_remaining: u64,
+    pub reserved_ids: UnorderedSet<u64>,
+    // the reserved ids in order, for metadata_index. Frozen with the set at the first draw
+    pub reserved_ids_sorted: Vec<u64>,
+
+    pub max_supply: u64,
+    pub title_template: String,
+    pub description: String,
+
+    pub seed_commitment: Vec<u8>,
+    pub revealed_seed: Option<Vec<u8>>,
+    pub draw_nonce: u64,
+
+    pub placeholder_metadata: Option<TokenMetadata>,
+    pub revealed: bool,
+    pub reveal_cursor: u64,
+    pub provenance_hash: Option<String>,
+    pub starting_index: Option<u64>,
+    pub asset_hashes: LookupMap<u64, AssetHashes>,
+    pub asset_hashes_count: u64,
 
     pub url_media_base: String,
     pub url_reference_base: String
 }
 
+/// Placeholder replaced by the token id when rendering `title_template`.
+pub const TITLE_ID_PLACEHOLDER: &str = "{id}";
+
+/// Collection parameters fixed at initialization, returned by `collection_config`.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CollectionConfig {
+    pub max_supply: u64,
+    pub title_template: String,
+    pub description: String,
+}
+
+/// Outcome of `nft_mint`, `filled` is below `requested` when the pool ran low.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintResult {
+    pub tokens: Vec<Token>,
+    pub requested: U128,
+    pub filled: U128,
+}
+
+/// Public inputs of the commit-reveal scheme, returned by `randomness_state`.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RandomnessState {
+    pub seed_commitment: Base64VecU8,
+    pub revealed_seed: Option<Base64VecU8>,
+    pub draws: u64,
+}
+
 const DATA_IMAGE_SVG_NEAR_ICON: &str = "data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 288 288'%3E%3Cg id='l' data-name='l'%3E%3Cpath d='M187.58,79.81l-30.1,44.69a3.2,3.2,0,0,0,4.75,4.2L191.86,103a1.2,1.2,0,0,1,2,.91v80.46a1.2,1.2,0,0,1-2.12.77L102.18,77.93A15.35,15.35,0,0,0,90.47,72.5H87.34A15.34,15.34,0,0,0,72,87.84V201.16A15.34,15.34,0,0,0,87.34,216.5h0a15.35,15.35,0,0,0,13.08-7.31l30.1-44.69a3.2,3.2,0,0,0-4.75-4.2L96.14,186a1.2,1.2,0,0,1-2-.91V104.61a1.2,1.2,0,0,1,2.12-.77l89.55,107.23a15.35,15.35,0,0,0,11.71,5.43h3.13A15.34,15.34,0,0,0,216,201.16V87.84A15.34,15.34,0,0,0,200.66,72.5h0A15.35,15.35,0,0,0,187.58,79.81Z'/%3E%3C/g%3E%3C/svg%3E";
 
 #[derive(BorshSerialize, BorshStorageKey)]
@@ -68,7 +177,21 @@ enum StorageKey {
     Approval,
     Royalties,
     Whitelist,
-    RandomMinting
+    PoolSwaps,
+    AssetHashes,
+    PhaseMinted,
+    MintedByAccount,
+    AuctionPurchases,
+    FtPrices,
+    ReservedIds,
+    AllowlistClaimed,
+    MintLists,
+    MintListMembers,
+    MintListConsumed,
+    UsedVoucherNonces,
+    RevenueDistributed,
+    FtRevenue,
+    FtStorageDeposits
 }
 
 #[near_bindgen]
@@ -91,6 +214,10 @@ impl Contract {
             U128(10),
             owner_id.to_string(),
             U128(500),
+            2331,
+            "Tokonami #{id}".to_string(),
+            "2331 TOKONAMI Ready for the Revolution".to_string(),
+            Base64VecU8(env::sha256(&[0u8; SEED_LEN])),
             "test".to_string(),
             "test".to_string()
         )
@@ -98,42 +225,89 @@ impl Contract {
 
     #[init]
     pub fn new(owner_id: ValidAccountId, metadata: NFTContractMetadata, mint_cost: U128,
-         royalties_account: AccountId, royalties_value: U128, url_media_base: String, url_reference_base: String) -> Self {
-        assert!(!env::state_exists(), "Already initialized");
+         royalties_account: AccountId, royalties_value: U128, max_supply: u64, title_template: String,
+         description: String, seed_commitment: Base64VecU8, url_media_base: String, url_reference_base: String) -> Self {
+        require(!env::state_exists(), ContractError::AlreadyInitialized);
         metadata.assert_valid();
+        require(max_supply > 0, ContractError::InvalidCollectionConfig("max_supply must be greater than 0"));
+        require(
+            title_template.contains(TITLE_ID_PLACEHOLDER),
+            ContractError::InvalidCollectionConfig("title_template must contain the {id} placeholder")
+        );
+        require(!description.is_empty(), ContractError::InvalidCollectionConfig("description cannot be empty"));
+        require(
+            seed_commitment.0.len() == SEED_LEN,
+            ContractError::InvalidCollectionConfig("seed commitment has to be 32 bytes")
+        );
+        let mut tokens = NonFungibleToken::new(
+            StorageKey::NonFungibleToken,
+            owner_id,
+            Some(StorageKey::TokenMetadata),
+            Some(StorageKey::Enumeration),
+            Some(StorageKey::Approval),
+            Some(StorageKey::Royalties)
+        );
+        if royalties_value.0 > 0 {
+            tokens.set_default_royalty(HashMap::from([(royalties_account.clone(), royalties_value.0)]));
+        }
         Self {
-            tokens: NonFungibleToken::new(
-                StorageKey::NonFungibleToken,
-                owner_id,
-                Some(StorageKey::TokenMetadata),
-                Some(StorageKey::Enumeration),
-                Some(StorageKey::Approval),
-                Some(StorageKey::Royalties)
-            ),
-            funds_beneficiary: royalties_account.clone(),
-            perpetual_royalties: HashMap::from([(royalties_account, royalties_value.0)]),
+            tokens,
+            funds_beneficiary: royalties_account,
+            revenue_accrued: 0,
+            revenue_withdrawn: 0,
+            revenue_splits: HashMap::new(),
+            revenue_distributed: LookupMap::new(StorageKey::RevenueDistributed),
+            royalty_migration_cursor: 0,
+            royalty_migration_from: HashMap::new(),
             metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
-            whitelist: LookupMap::new(StorageKey::Whitelist),
+            whitelist: UnorderedMap::new(StorageKey::Whitelist),
+            allowlist_root: None,
+            allowlist_claimed: LookupMap::new(StorageKey::AllowlistClaimed),
+            mint_lists: UnorderedMap::new(StorageKey::MintLists),
+            mint_list_members: LookupSet::new(StorageKey::MintListMembers),
+            mint_list_consumed: LookupMap::new(StorageKey::MintListConsumed),
+            voucher_key: None,
+            used_voucher_nonces: LookupSet::new(StorageKey::UsedVoucherNonces),
             mint_cost: mint_cost.0,
-            sales_locked: true,
-            only_whitelist: true,
-            random_minting: Vector::new(StorageKey::RandomMinting),
+            sales_locked: false,
+            sale_phases: Vec::new(),
+            phase_minted: LookupMap::new(StorageKey::PhaseMinted),
+            public_mint_limits: PublicMintLimits::default(),
+            minted_by_account: LookupMap::new(StorageKey::MintedByAccount),
+            dutch_auction: None,
+            auction_last_price: None,
+            auction_purchases: LookupMap::new(StorageKey::AuctionPurchases),
+            auction_paid: 0,
+            auction_quantity: 0,
+            ft_prices: UnorderedMap::new(StorageKey::FtPrices),
+            ft_revenue: LookupMap::new(StorageKey::FtRevenue),
+            ft_storage_deposits: LookupMap::new(StorageKey::FtStorageDeposits),
+            ft_storage_deposited: 0,
+            pool_swaps: LookupMap::new(StorageKey::PoolSwaps),
+            pool_remaining: max_supply,
+            reserved_ids: UnorderedSet::new(StorageKey::ReservedIds),
+            reserved_ids_sorted: Vec::new(),
+            max_supply,
+            title_template,
+            description,
+            seed_commitment: seed_commitment.0,
+            revealed_seed: None,
+            draw_nonce: 0,
+            placeholder_metadata: None,
+            revealed: false,
+            reveal_cursor: 0,
+            provenance_hash: None,
+            starting_index: None,
+            asset_hashes: LookupMap::new(StorageKey::AssetHashes),
+            asset_hashes_count: 0,
             url_media_base,
             url_reference_base
         }
     }
 
-    pub fn initilize_random_generator(&mut self) -> bool {
-        let initial_len: u128 = self.random_minting.len().into();
-        let mut i: u128 = 1;
-        while i <= 50 {
-            if i + initial_len > 2331 {
-                return true
-            } 
-            self.random_minting.push(&(&i + &initial_len));
-            i = i + 1
-        }
-        false
+    //number of ids left in the random pool
+    pub fn remaining_supply(&self) -> u64 {
+        self.pool_remaining
     }
 
     /// Mint a new token with ID=`token_id` belonging to `receiver_id`.
@@ -145,64 +319,228 @@ impl Contract {
     /// `self.tokens.mint` will enforce `predecessor_account_id` to equal the `owner_id` given in
     /// initialization call to `new`.
 
-    //minter must be whitelisted possibility to mint multiple nfts in batch
+    //minter must be whitelisted possibility to mint multiple nfts in batch.
+    //the payer can mint to another receiver_id, allowances and caps still apply to the payer.
+    //when fewer NFTs are left than requested, mints what is left and refunds the rest of the deposit.
+    //in whitelist only phases, `allowlist` proves the allowance instead of the on chain whitelist.
+    //`list` mints from a named mint list at its price instead of the current sale phase
     #[payable]
     pub fn nft_mint(
         &mut self,
-        quantity: U128
-    ) -> Vec<Token> {
+        quantity: U128,
+        receiver_id: Option<ValidAccountId>,
+        allowlist: Option<AllowlistProof>,
+        list: Option<String>
+    ) -> MintResult {
         let account_id: AccountId = env::predecessor_account_id();
-        let allowance: u128 = self.whitelist.get(&account_id).unwrap_or(0);
-
-        assert!(!self.sales_locked, "sales locked");
-        if self.only_whitelist {
-            assert!(&allowance >= &quantity.0, "Whitelist error: this account has no allowance for minitng this amount of NFTs");
-            self.whitelist.insert(&account_id, &(allowance - quantity.0));
-        }
+        let receiver_id: AccountId = receiver_id.map(|receiver_id| receiver_id.into()).unwrap_or_else(|| account_id.clone());
+
+        require(!self.sales_locked, ContractError::SalesLocked);
+        let filled = self.fillable(quantity.0);
+        let (price, from_auction) = match &list {
+            Some(name) => (self.debit_mint_list(name, &account_id, filled), false),
+            None => self.assert_phase_allows_mint(&account_id, filled, allowlist.as_ref()),
+        };
         
-        let mut return_vector = Vec::new();
-
         let initial_storage_usage = env::storage_usage();
+        let memo = if receiver_id != account_id { Some(format!("paid by {}", account_id)) } else { None };
+        let tokens = self.internal_mint_random(&account_id, &receiver_id, filled, price, memo);
+        //mints from a list don't count against the public limits
+        if list.is_none() {
+            self.record_minted(&account_id, filled as u32);
+        }
+        self.accrue_revenue(price * filled);
+        if from_auction {
+            self.record_auction_purchase(&account_id, filled as u32, price);
+        }
+        refund_deposit_mint(env::storage_usage().saturating_sub(initial_storage_usage), price * filled);
+        MintResult { tokens, requested: quantity, filled: U128(filled) }
+    }
 
+    //draws `quantity` ids from the random pool for account_id and mints them to receiver_id
+    fn internal_mint_random(
+        &mut self,
+        account_id: &AccountId,
+        receiver_id: &AccountId,
+        quantity: u128,
+        price: u128,
+        memo: Option<String>
+    ) -> Vec<Token> {
+        let mut return_vector = Vec::new();
+        let mut token_ids = Vec::new();
+        let mut pool_indexes = Vec::new();
+
+        let block_seed = env::random_seed();
+        let first_nonce = self.draw_nonce;
+        let pool_len = self.pool_remaining;
         let mut i: u128 = 0;
-        let mut random_seed: u64 = (*env::random_seed().get(0).unwrap()).into();
-        random_seed = random_seed + 1;
-        let mut random_range: u64;
-        let mut current_id;
-        while i < quantity.0 {
-            random_range = (u64::MAX / random_seed) % self.random_minting.len();
-            current_id = self.random_minting.swap_remove(random_range);
+        while i < quantity {
+            let pool_index = draw_index(
+                &block_seed,
+                account_id,
+                self.draw_nonce,
+                i as u64,
+                self.pool_remaining
+            );
+            let current_id = self.pool_take(pool_index);
+            self.draw_nonce += 1;
             return_vector.push( 
                 self.tokens.internal_mint( 
                     current_id.to_string(), 
-                    account_id.clone().try_into().unwrap(), 
-                    Some(TokenMetadata {
-                        title: Some(format!("Tokonami #{}", &current_id)),
-                        description: Some("2331 TOKONAMI Ready for the Revolution".to_string()),
-                        media: Some(format!("{}/{}.png", self.url_media_base, &current_id)),
-                        media_hash: None,
-                        copies: None,
-                        issued_at: None,
-                        expires_at: None,
-                        starts_at: None,
-                        updated_at: None,
-                        extra: None,
-                        reference: Some(format!("{}/{}.json", self.url_reference_base, &current_id)),
-                        reference_hash: None,
-
-                        // special metadata
-                        nft_type: Some((&current_id % 3 + 1).to_string())
-                    }),
-                    self.mint_cost,
-                    self.perpetual_royalties.clone()
+                    receiver_id.clone().try_into().unwrap_or_else(|_| ContractError::InvalidAccountId.panic()), 
+                    Some(self.token_metadata(u128::from(current_id))),
+                    price,
+                    None,
+                    memo.clone()
                 )
             );
+            token_ids.push(current_id);
+            pool_indexes.push(pool_index);
             i = i + 1;
         }
-        refund_deposit_mint(env::storage_usage() - initial_storage_usage, self.mint_cost * quantity.0);
+        TokenDraw {
+            minter: account_id,
+            block_seed: Base64VecU8(block_seed),
+            first_nonce,
+            pool_len,
+            token_ids: &token_ids,
+            pool_indexes: &pool_indexes
+        }.emit();
         return_vector
     }
 
+    //collection parameters set at initialization
+    pub fn collection_config(&self) -> CollectionConfig {
+        CollectionConfig {
+            max_supply: self.max_supply,
+            title_template: self.title_template.clone(),
+            description: self.description.clone(),
+        }
+    }
+
+    //reveals the seed committed at initialization, once the collection sold out or the sale ended
+    #[payable]
+    pub fn reveal_seed(&mut self, seed: Base64VecU8) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(self.revealed_seed.is_none(), ContractError::SeedAlreadyRevealed);
+        require(self.sale_ended(), ContractError::SaleNotEnded);
+        require(matches_commitment(&seed.0, &self.seed_commitment), ContractError::SeedMismatch);
+
+        if self.placeholder_metadata.is_some() {
+            self.starting_index = Some(self.compute_starting_index(&seed.0));
+        }
+        self.revealed_seed = Some(seed.0.clone());
+        SeedReveal { seed, starting_index: self.starting_index }.emit();
+        true
+    }
+
+    //inputs needed to re-derive every draw from the random pool
+    pub fn randomness_state(&self) -> RandomnessState {
+        RandomnessState {
+            seed_commitment: Base64VecU8(self.seed_commitment.clone()),
+            revealed_seed: self.revealed_seed.clone().map(Base64VecU8),
+            draws: self.draw_nonce,
+        }
+    }
+
+    //every id of the collection has been drawn from the random pool
+    fn sold_out(&self) -> bool {
+        self.pool_remaining == 0
+    }
+
+    //how many of `quantity` NFTs can still be minted, panics when none is left
+    fn fillable(&self, quantity: u128) -> u128 {
+        require(!self.sold_out(), ContractError::SoldOut);
+        std::cmp::min(quantity, u128::from(self.pool_remaining))
+    }
+
+    //id sitting at `slot` of the random pool
+    fn pool_value(&self, slot: u64) -> u64 {
+        self.pool_swaps.get(&slot).unwrap_or(slot + 1)
+    }
+
+    //removes the id at `slot` from the random pool, the last id of the pool takes its place.
+    //ids only move down, so the entry of the last slot is never needed again
+    fn pool_take(&mut self, slot: u64) -> u64 {
+        let id = self.pool_value(slot);
+        let last = self.pool_remaining - 1;
+        let last_id = self.pool_swaps.remove(&last).unwrap_or(last + 1);
+        if slot != last {
+            self.pool_swaps.insert(&slot, &last_id);
+        }
+        self.pool_remaining = last;
+        id
+    }
+
+    //the owner can no longer change the drop parameters
+    fn sale_started(&self) -> bool {
+        self.draw_nonce > 0 || self.any_phase_started()
+    }
+
+    //collection id whose media a token shows, shifted by the starting index once the seed is
+    //revealed. Reserved ids keep their own media and are skipped by the rotation of the others
+    fn metadata_index(&self, id: u128) -> u128 {
+        let reserved = &self.reserved_ids_sorted;
+        let offset = match self.starting_index {
+            Some(offset) if reserved.binary_search(&(id as u64)).is_err() => offset,
+            _ => return id,
+        };
+        let id = id as u64;
+        let rotated = self.max_supply - reserved.len() as u64;
+        let rank = id - 1 - reserved.iter().take_while(|reserved_id| **reserved_id < id).count() as u64;
+
+        //the id holding position (rank + offset) among the ids left in the pool
+        let mut index = (rank + offset) % rotated + 1;
+        for reserved_id in reserved {
+            if *reserved_id <= index {
+                index += 1;
+            }
+        }
+        u128::from(index)
+    }
+
+    //metadata of a collection id, the shared placeholder while a delayed reveal is pending
+    fn token_metadata(&self, id: u128) -> TokenMetadata {
+        if !self.revealed {
+            if let Some(placeholder) = &self.placeholder_metadata {
+                return placeholder.clone();
+            }
+        }
+        let index = self.metadata_index(id);
+        let mut metadata = TokenMetadata {
+            title: Some(self.token_title(id)),
+            description: Some(self.description.clone()),
+            media: Some(format!("{}/{}.png", self.url_media_base, &index)),
+            media_hash: None,
+            copies: None,
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: Some(format!("{}/{}.json", self.url_reference_base, &index)),
+            reference_hash: None,
+
+            // special metadata
+            nft_type: Some((&index % 3 + 1).to_string())
+        };
+        //once hashes were uploaded every token must carry valid ones
+        if self.asset_hashes_count > 0 {
+            if let Some(hashes) = self.asset_hashes.get(&(index as u64)) {
+                metadata.media_hash = Some(hashes.media_hash);
+                metadata.reference_hash = Some(hashes.reference_hash);
+            }
+            metadata.assert_valid();
+        }
+        metadata
+    }
+
+    //renders the title template for a collection id
+    fn token_title(&self, id: u128) -> String {
+        self.title_template.replace(TITLE_ID_PLACEHOLDER, &id.to_string())
+    }
+
     //burn token
     #[payable]
     pub fn nft_burn(
@@ -211,70 +549,30 @@ impl Contract {
         token_id: &TokenId,
     ) -> bool {
         assert_one_or_more_yocto();
-        self.tokens.internal_transfer(
+        let (owner, _) = self.tokens.internal_transfer(
             sender_id,
             &"system".to_string(),
             token_id,
             None,
             None
         );
-        let owner = self.tokens.owner_by_id.get(&token_id).unwrap();
-        
-        NftBurn { owner_id: &owner, token_ids: &[&token_id], memo: None, authorized_id: None }.emit();
-        true
-    }
 
-    //add people to whitelist
-    #[payable]
-    pub fn add_to_whitelist(
-        &mut self,
-        whitelist_map: HashMap<AccountId, u128>
-    ) -> bool {
-        assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
-        assert_one_or_more_yocto();
-        for key in whitelist_map.keys() {
-            self.whitelist.insert(key, whitelist_map.get(key).unwrap());
-        }
+        NftBurn { owner_id: &owner, token_ids: &[&token_id], memo: None, authorized_id: None }.emit();
         true
     }
 
-    //add people to whitelist
-    pub fn is_whitelist(
-        &self,
-        account_id: AccountId
-    ) -> u128 {
-        self.whitelist.get(&account_id).unwrap_or(0)
-    }
-
-    #[payable]
-    pub fn retrieve_funds(&mut self, quantity: U128) -> Promise {
-        assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
-        assert_one_or_more_yocto();
-
-        Promise::new(self.funds_beneficiary.clone()).transfer(quantity.0)
-    }
-
     #[payable]
     pub fn unlock_sales(&mut self, sales_lock: bool) -> bool {
-        assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
         assert_one_or_more_yocto();
 
         self.sales_locked = sales_lock;
         true
     }
 
-    #[payable]
-    pub fn unlock_whitelist(&mut self, whitelist_lock: bool) -> bool {
-        assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
-        assert_one_or_more_yocto();
-
-        self.only_whitelist = whitelist_lock;
-        true
-    }
-
     #[payable]
     pub fn change_mint_cost(&mut self, mint_cost: U128) -> bool {
-        assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
         assert_one_or_more_yocto();
 
         self.mint_cost = mint_cost.0;
@@ -316,7 +614,92 @@ near_contract_standards::impl_non_fungible_token_enumeration!(Contract, tokens);
 #[near_bindgen]
 impl NonFungibleTokenMetadataProvider for Contract {
     fn nft_metadata(&self) -> NFTContractMetadata {
-        self.metadata.get().unwrap()
+        self.metadata.get().unwrap_or_else(|| ContractError::MissingState("the contract metadata").panic())
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod pool_tests {
+    use crate::test_utils::{drain_pool, new_contract, slot_pickers};
+
+    #[test]
+    fn pool_yields_every_id_once() {
+        for pick in slot_pickers() {
+            let mut contract = new_contract(10);
+            assert_eq!(drain_pool(&mut contract, pick), (1..=10).collect::<Vec<u64>>());
+        }
+    }
+
+    #[test]
+    fn single_id_pool() {
+        let mut contract = new_contract(1);
+        assert_eq!(drain_pool(&mut contract, |_, _| 0), vec![1]);
+        assert!(contract.sold_out());
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod metadata_index_tests {
+    use crate::test_utils::new_contract;
+
+    //media shown by every id of a 10 id collection with `reserved` ids and a starting index
+    fn shown_media(reserved: Vec<u64>, starting_index: u64) -> Vec<u128> {
+        let mut contract = new_contract(10);
+        contract.reserve_ids(reserved);
+        contract.starting_index = Some(starting_index);
+        (1..=10).map(|id| contract.metadata_index(id)).collect()
+    }
+
+    //the reserved ids keep their media, the others share the rest without repeats
+    fn assert_rotation(reserved: Vec<u64>) {
+        for starting_index in 0..12 {
+            let shown = shown_media(reserved.clone(), starting_index);
+            let mut rotated = Vec::new();
+            for (id, index) in (1..=10u64).zip(shown) {
+                if reserved.contains(&id) {
+                    assert_eq!(index, u128::from(id));
+                } else {
+                    assert!(!reserved.contains(&(index as u64)), "{} shows reserved media {}", id, index);
+                    rotated.push(index as u64);
+                }
+            }
+            rotated.sort_unstable();
+            let expected: Vec<u64> = (1..=10).filter(|id| !reserved.contains(id)).collect();
+            assert_eq!(rotated, expected, "starting index {}", starting_index);
+        }
+    }
+
+    #[test]
+    fn no_starting_index_keeps_the_ids() {
+        let mut contract = new_contract(10);
+        contract.reserve_ids(vec![3]);
+        assert_eq!((1..=10).map(|id| contract.metadata_index(id)).collect::<Vec<u128>>(), (1..=10).collect::<Vec<u128>>());
+    }
+
+    #[test]
+    fn rotation_without_reserved_ids() {
+        assert_eq!(shown_media(vec![], 3), vec![4, 5, 6, 7, 8, 9, 10, 1, 2, 3]);
+        assert_rotation(vec![]);
+    }
+
+    #[test]
+    fn reserved_ids_at_the_start() {
+        assert_eq!(shown_media(vec![1, 2], 1), vec![1, 2, 4, 5, 6, 7, 8, 9, 10, 3]);
+        assert_rotation(vec![1, 2]);
+    }
+
+    #[test]
+    fn reserved_ids_in_the_middle() {
+        assert_eq!(shown_media(vec![5, 6], 1), vec![2, 3, 4, 7, 5, 6, 8, 9, 10, 1]);
+        assert_rotation(vec![5, 6]);
+        assert_rotation(vec![4, 7]);
+    }
+
+    #[test]
+    fn reserved_ids_at_the_end() {
+        assert_eq!(shown_media(vec![9, 10], 1), vec![2, 3, 4, 5, 6, 7, 8, 1, 9, 10]);
+        assert_rotation(vec![9, 10]);
+        assert_rotation(vec![1, 5, 10]);
     }
 }
 
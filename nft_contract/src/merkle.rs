@@ -0,0 +1,119 @@
+//! Merkle allowlist: the owner publishes the root of a tree of `(account, allowance)` leaves and
+//! each minter brings the proof of its own leaf, so the list itself never goes on chain.
+//!
+//! * leaf: `sha256(0x00 || account_id || ":" || allowance)`, allowance in decimal
+//! * node: `sha256(0x01 || min(left, right) || max(left, right))`
+//!
+//! Sorting the pair makes a proof a plain list of sibling hashes, with no left/right flags.
+//! On an odd level the last node goes up unchanged. [`MerkleTree`] builds the root and the
+//! proofs off chain from the account list.
+
+use near_sdk::AccountId;
+
+/// Length in bytes of a leaf, node or root hash.
+pub const HASH_LEN: usize = 32;
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+#[cfg(target_arch = "wasm32")]
+fn sha256(data: &[u8]) -> Vec<u8> {
+    near_sdk::env::sha256(data)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn sha256(data: &[u8]) -> Vec<u8> {
+    use sha2::Digest;
+    sha2::Sha256::digest(data).to_vec()
+}
+
+/// Hash of the leaf giving `allowance` mints to `account_id`.
+pub fn leaf_hash(account_id: &AccountId, allowance: u32) -> Vec<u8> {
+    let mut input = vec![LEAF_PREFIX];
+    input.extend_from_slice(format!("{}:{}", account_id, allowance).as_bytes());
+    sha256(&input)
+}
+
+fn node_hash(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut input = Vec::with_capacity(1 + 2 * HASH_LEN);
+    input.push(NODE_PREFIX);
+    input.extend_from_slice(left);
+    input.extend_from_slice(right);
+    sha256(&input)
+}
+
+/// Checks that `leaf` belongs to the tree of `root`.
+pub fn verify(root: &[u8], leaf: Vec<u8>, proof: &[Vec<u8>]) -> bool {
+    proof.iter().fold(leaf, |hash, sibling| node_hash(&hash, sibling)) == root
+}
+
+/// Off chain builder of the allowlist root and of the proof of every leaf.
+pub struct MerkleTree {
+    // levels[0] are the leaves in list order, the last level holds the root
+    levels: Vec<Vec<Vec<u8>>>,
+}
+
+impl MerkleTree {
+    /// `entries` must not be empty, an account listed twice gets two independent leaves.
+    pub fn new(entries: &[(AccountId, u32)]) -> Self {
+        assert!(!entries.is_empty(), "Allowlist cannot be empty");
+        let mut levels =
+            vec![entries.iter().map(|(account_id, allowance)| leaf_hash(account_id, *allowance)).collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| if pair.len() == 2 { node_hash(&pair[0], &pair[1]) } else { pair[0].clone() })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    pub fn root(&self) -> Vec<u8> {
+        self.levels.last().unwrap()[0].clone()
+    }
+
+    /// Sibling hashes from the leaf at `index` up to the root.
+    pub fn proof(&self, mut index: usize) -> Vec<Vec<u8>> {
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < level.len() {
+                proof.push(level[sibling].clone());
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn entries(count: usize) -> Vec<(AccountId, u32)> {
+        (0..count).map(|i| (format!("account{}.near", i), i as u32 + 1)).collect()
+    }
+
+    #[test]
+    fn every_leaf_verifies() {
+        for count in 1..10 {
+            let entries = entries(count);
+            let tree = MerkleTree::new(&entries);
+            for (index, (account_id, allowance)) in entries.iter().enumerate() {
+                assert!(verify(&tree.root(), leaf_hash(account_id, *allowance), &tree.proof(index)));
+            }
+        }
+    }
+
+    #[test]
+    fn other_allowance_fails() {
+        let entries = entries(5);
+        let tree = MerkleTree::new(&entries);
+        assert!(!verify(&tree.root(), leaf_hash(&entries[2].0, 100), &tree.proof(2)));
+        assert!(!verify(&tree.root(), leaf_hash(&entries[2].0, 3), &tree.proof(1)));
+    }
+}
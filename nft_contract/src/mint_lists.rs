@@ -0,0 +1,102 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::utils::refund_deposit;
+use near_sdk::json_types::{U128, U64};
+use near_sdk::Balance;
+
+/// Tier of the drop (OG, partners...) with its own price, allowance per member and window.
+/// Mints from a list happen while `start <= block_timestamp < end`, independently of the
+/// sale phases, and don't count against the public limits.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintList {
+    pub price: U128,
+    pub allowance: u32,
+    pub start: U64,
+    pub end: U64,
+}
+
+impl MintList {
+    fn is_active(&self, now: u64) -> bool {
+        self.start.0 <= now && now < self.end.0
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    //creates the list called `name` or updates it, members keep what they already minted
+    #[payable]
+    pub fn set_mint_list(&mut self, name: String, list: MintList) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(!name.is_empty(), ContractError::InvalidMintList("name cannot be empty"));
+        require(list.start.0 < list.end.0, ContractError::InvalidMintList("must start before it ends"));
+
+        let initial_storage_usage = env::storage_usage();
+        self.mint_lists.insert(&name, &list);
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+        true
+    }
+
+    //adds members to a list, the owner pays for their storage
+    #[payable]
+    pub fn add_to_mint_list(&mut self, name: String, account_ids: Vec<AccountId>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(self.mint_lists.get(&name).is_some(), ContractError::MintListNotFound);
+
+        let initial_storage_usage = env::storage_usage();
+        for account_id in account_ids {
+            self.mint_list_members.insert(&(name.clone(), account_id));
+        }
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+        true
+    }
+
+    //drops members from a list, the storage they used is refunded to the owner
+    #[payable]
+    pub fn remove_from_mint_list(&mut self, name: String, account_ids: Vec<AccountId>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+
+        let initial_storage_usage = env::storage_usage();
+        for account_id in account_ids {
+            self.mint_list_members.remove(&(name.clone(), account_id));
+        }
+        let released = initial_storage_usage.saturating_sub(env::storage_usage());
+        if released > 0 {
+            Promise::new(self.tokens.owner_id.clone()).transfer(Balance::from(released) * env::storage_byte_cost());
+        }
+        true
+    }
+
+    pub fn mint_lists(&self) -> Vec<(String, MintList)> {
+        self.mint_lists.to_vec()
+    }
+
+    //NFTs account_id can still mint from the list, 0 when it's not a member
+    pub fn mint_list_allowance(&self, name: String, account_id: ValidAccountId) -> u32 {
+        let key = (name, account_id.into());
+        match self.mint_lists.get(&key.0) {
+            Some(list) if self.mint_list_members.contains(&key) => {
+                list.allowance.saturating_sub(self.mint_list_consumed.get(&key).unwrap_or(0))
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl Contract {
+    //checks account_id can mint quantity from the list now, debits its allowance and
+    //returns the price of one token in the list
+    pub(crate) fn debit_mint_list(&mut self, name: &str, account_id: &AccountId, quantity: u128) -> u128 {
+        let list = self.mint_lists.get(&name.to_string()).unwrap_or_else(|| ContractError::MintListNotFound.panic());
+        require(list.is_active(env::block_timestamp()), ContractError::MintListInactive);
+        let key = (name.to_string(), account_id.clone());
+        require(self.mint_list_members.contains(&key), ContractError::NotInMintList);
+
+        let consumed = u128::from(self.mint_list_consumed.get(&key).unwrap_or(0));
+        require(consumed + quantity <= u128::from(list.allowance), ContractError::MintListAllowanceExceeded);
+        self.mint_list_consumed.insert(&key, &((consumed + quantity) as u32));
+        list.price.0
+    }
+}
@@ -0,0 +1,63 @@
+use crate::*;
+
+/// Length of a hex encoded sha256 hash.
+const PROVENANCE_HASH_LEN: usize = 64;
+
+/// The provenance hash is the hex encoded sha256 of the `reference_hash` of every token,
+/// concatenated in id order (see `json_generation/provenance.js`).
+fn assert_valid_provenance_hash(provenance_hash: &str) {
+    require(
+        provenance_hash.len() == PROVENANCE_HASH_LEN
+            && provenance_hash.chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()),
+        ContractError::InvalidProvenanceHash
+    );
+}
+
+#[near_bindgen]
+impl Contract {
+    //commits to the ordering of the collection, can't be changed once the sale started
+    #[payable]
+    pub fn set_provenance_hash(&mut self, provenance_hash: String) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(!self.sale_started(), ContractError::SaleAlreadyStarted);
+        assert_valid_provenance_hash(&provenance_hash);
+
+        self.provenance_hash = Some(provenance_hash);
+        true
+    }
+
+    pub fn provenance_hash(&self) -> Option<String> {
+        self.provenance_hash.clone()
+    }
+
+    //offset applied to the token ids of a delayed reveal, fixed when the seed is revealed
+    pub fn starting_index(&self) -> Option<u64> {
+        self.starting_index
+    }
+
+    //position in the provenance ordering of the metadata shown by token_id
+    pub fn provenance_index(&self, token_id: TokenId) -> Option<u64> {
+        let id: u64 = token_id.parse().ok()?;
+        if id == 0 || id > self.max_supply {
+            return None;
+        }
+        Some(self.metadata_index(u128::from(id)) as u64)
+    }
+}
+
+impl Contract {
+    //starting index derived from the revealed seed, the provenance hash and the randomness of
+    //the block fixing it, which the owner can't know while the sale runs
+    pub(crate) fn compute_starting_index(&self, seed: &[u8]) -> u64 {
+        let mut input = seed.to_vec();
+        if let Some(provenance_hash) = &self.provenance_hash {
+            input.extend_from_slice(provenance_hash.as_bytes());
+        }
+        input.extend_from_slice(&env::random_seed());
+        let hash = env::sha256(&input);
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&hash[..8]);
+        u64::from_le_bytes(word) % self.max_supply
+    }
+}
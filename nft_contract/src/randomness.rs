@@ -0,0 +1,45 @@
+//! Randomness used to pick token ids out of the random pool and, for a delayed reveal, to map
+//! them to their metadata.
+//!
+//! Each token is drawn with [`draw_index`] from the block randomness of the mint, whose inputs
+//! are all logged in a `token_draw` event, so holders can replay the pool from `1..=max_supply`
+//! in nonce order and check every assignment. The owner commits to `sha256(seed)` when
+//! initializing the contract and reveals `seed` once the sale ended. In a delayed reveal the
+//! seed, mixed with the randomness of that block, fixes the starting index that shifts the
+//! metadata of every token, so the art behind the placeholders is not known until then.
+
+use near_sdk::{env, AccountId};
+
+/// Length in bytes of the owner seed and of its sha256 commitment.
+pub const SEED_LEN: usize = 32;
+
+/// Position in the random pool drawn for one token of a batch.
+///
+/// * `block_seed`: the full 32 byte `env::random_seed()` of the mint receipt
+/// * `minter`: the account that called the mint
+/// * `nonce`: number of draws made by the contract before this one
+/// * `batch_index`: position of the token inside the mint call
+/// * `pool_len`: number of ids left in the pool, must be greater than 0
+pub fn draw_index(
+    block_seed: &[u8],
+    minter: &AccountId,
+    nonce: u64,
+    batch_index: u64,
+    pool_len: u64,
+) -> u64 {
+    let mut input = Vec::with_capacity(block_seed.len() + minter.len() + 16);
+    input.extend_from_slice(block_seed);
+    input.extend_from_slice(minter.as_bytes());
+    input.extend_from_slice(&nonce.to_le_bytes());
+    input.extend_from_slice(&batch_index.to_le_bytes());
+
+    let hash = env::sha256(&input);
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&hash[..8]);
+    u64::from_le_bytes(word) % pool_len
+}
+
+/// Checks that `seed` is the preimage of `commitment`.
+pub fn matches_commitment(seed: &[u8], commitment: &[u8]) -> bool {
+    seed.len() == SEED_LEN && env::sha256(seed) == commitment
+}
@@ -0,0 +1,87 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::utils::refund_deposit;
+
+#[near_bindgen]
+impl Contract {
+    //takes ids out of the random pool, only possible before the first draw
+    #[payable]
+    pub fn reserve_ids(&mut self, ids: Vec<u64>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(self.draw_nonce == 0, ContractError::SaleAlreadyStarted);
+
+        for id in ids {
+            require(id >= 1 && id <= self.max_supply, ContractError::IndexOutOfRange);
+            if !self.reserved_ids.contains(&id) {
+                let slot = self.pool_slot_before_draws(id);
+                self.pool_take(slot);
+                self.reserved_ids.insert(&id);
+                let position = self.reserved_ids_sorted.binary_search(&id).unwrap_or_else(|position| position);
+                self.reserved_ids_sorted.insert(position, id);
+            }
+        }
+        true
+    }
+
+    pub fn reserved_ids(&self) -> Vec<u64> {
+        self.reserved_ids.to_vec()
+    }
+
+    //mints a reserved id to receiver_id, the owner pays for its storage
+    #[payable]
+    pub fn mint_reserved(&mut self, token_id: TokenId, receiver_id: ValidAccountId) -> Token {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        let id: u64 = token_id.parse().unwrap_or_else(|_| ContractError::InvalidCollectionIndex.panic());
+        require(self.reserved_ids.contains(&id), ContractError::TokenNotReserved);
+
+        let initial_storage_usage = env::storage_usage();
+        let token = self.tokens.internal_mint(
+            id.to_string(),
+            receiver_id,
+            Some(self.token_metadata(u128::from(id))),
+            0,
+            None,
+            Some("reserved".to_string())
+        );
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+        token
+    }
+}
+
+impl Contract {
+    //slot of `id` in the random pool. Before the first draw an id only leaves its own slot
+    //to fill the slot of a reserved id, so those are the only places to look at
+    fn pool_slot_before_draws(&self, id: u64) -> u64 {
+        std::iter::once(id)
+            .chain(self.reserved_ids.iter())
+            .map(|candidate| candidate - 1)
+            .find(|slot| *slot < self.pool_remaining && self.pool_value(*slot) == id)
+            .unwrap_or_else(|| ContractError::IdNotInPool.panic())
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::test_utils::{drain_pool, new_contract, slot_pickers};
+
+    #[test]
+    fn pool_skips_reserved_ids() {
+        for pick in slot_pickers() {
+            let mut contract = new_contract(10);
+            contract.reserve_ids(vec![1, 5, 10, 5]);
+            assert_eq!(contract.pool_remaining, 7);
+            assert_eq!(drain_pool(&mut contract, pick), vec![2, 3, 4, 6, 7, 8, 9]);
+        }
+    }
+
+    #[test]
+    fn reserving_a_swapped_id_finds_its_slot() {
+        let mut contract = new_contract(10);
+        //10 moves into the slot of 2, then 9 takes that slot when 10 is reserved
+        contract.reserve_ids(vec![2, 10]);
+        assert_eq!(contract.pool_slot_before_draws(9), 1);
+        contract.reserve_ids(vec![9]);
+        assert_eq!(drain_pool(&mut contract, |_, _| 0), vec![1, 3, 4, 5, 6, 7, 8]);
+    }
+}
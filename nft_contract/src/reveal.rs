@@ -0,0 +1,92 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::events::NftMetadataUpdate;
+use near_contract_standards::non_fungible_token::utils::refund_deposit;
+use near_sdk::Gas;
+
+/// Gas left untouched by a reveal page so the progress can still be saved.
+const GAS_RESERVED_FOR_REVEAL: Gas = 20_000_000_000_000;
+/// Upper bound of tokens rewritten per call, keeps the metadata update event under the log limit.
+const MAX_REVEAL_PAGE: usize = 500;
+
+/// Progress of a delayed reveal, returned by `reveal_state`.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RevealState {
+    pub delayed_reveal: bool,
+    pub revealed: bool,
+    pub next_id: u64,
+    pub max_supply: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    //mints every token with the shared placeholder until the owner calls reveal
+    #[payable]
+    pub fn set_placeholder_metadata(&mut self, placeholder: TokenMetadata) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(self.draw_nonce == 0, ContractError::SaleAlreadyStarted);
+
+        self.placeholder_metadata = Some(placeholder);
+        true
+    }
+
+    //writes the final metadata of the minted tokens, shifted by the starting index, one gas
+    //bounded page per call. The first call fixes the parameters, later calls must repeat them
+    //until it returns true
+    #[payable]
+    pub fn reveal(&mut self, base_media: String, base_reference: String, provenance_hash: Option<String>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(self.placeholder_metadata.is_some(), ContractError::NotDelayedReveal);
+        require(provenance_hash == self.provenance_hash, ContractError::ProvenanceMismatch);
+        require(self.starting_index.is_some(), ContractError::SeedNotRevealed);
+
+        if !self.revealed {
+            self.url_media_base = base_media;
+            self.url_reference_base = base_reference;
+            self.revealed = true;
+            self.reveal_cursor = 1;
+        } else {
+            require(
+                base_media == self.url_media_base && base_reference == self.url_reference_base,
+                ContractError::RevealParamsChanged
+            );
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        let mut updated: Vec<String> = Vec::new();
+        while self.reveal_cursor <= self.max_supply
+            && updated.len() < MAX_REVEAL_PAGE
+            && env::prepaid_gas() - env::used_gas() > GAS_RESERVED_FOR_REVEAL
+        {
+            let token_id = self.reveal_cursor.to_string();
+            if self.tokens.owner_by_id.contains_key(&token_id) {
+                let metadata = self.token_metadata(u128::from(self.reveal_cursor));
+                self.tokens.token_metadata_by_id
+                    .as_mut()
+                    .unwrap_or_else(|| ContractError::MissingState("the token metadata").panic())
+                    .insert(&token_id, &metadata);
+                updated.push(token_id);
+            }
+            self.reveal_cursor += 1;
+        }
+
+        if !updated.is_empty() {
+            let token_ids: Vec<&str> = updated.iter().map(|token_id| token_id.as_str()).collect();
+            NftMetadataUpdate { token_ids: &token_ids, memo: Some("reveal") }.emit();
+        }
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+        self.reveal_cursor > self.max_supply
+    }
+
+    pub fn reveal_state(&self) -> RevealState {
+        RevealState {
+            delayed_reveal: self.placeholder_metadata.is_some(),
+            revealed: self.revealed,
+            next_id: self.reveal_cursor,
+            max_supply: self.max_supply,
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::*;
+use crate::events::FundsWithdrawn;
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, Balance, Gas, PromiseResult};
+use std::collections::HashMap;
+
+/// Shares of the split table are in basis points and must add up to this.
+const TOTAL_BASIS_POINTS: u32 = 10_000;
+/// Bounds the number of transfers made by `distribute_funds`.
+const MAX_REVENUE_SPLITS: usize = 10;
+const GAS_FOR_RESOLVE_REVENUE_TRANSFER: Gas = 5_000_000_000_000;
+
+#[ext_contract(ext_revenue)]
+trait RevenueTransferResolver {
+    fn resolve_revenue_transfer(&mut self, beneficiary: AccountId, amount: U128) -> bool;
+}
+
+/// NEAR mint revenue of the contract, returned by `revenue_ledger`. Storage deposits and
+/// fungible token payments are not part of it.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RevenueLedger {
+    pub accrued: U128,
+    pub withdrawn: U128,
+    // auction rebates buyers can still claim, at the floor price until the auction settles
+    pub rebate_liability: U128,
+    pub withdrawable: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    //sends up to `quantity` of the withdrawable revenue to the funds beneficiary,
+    //only while there is no split table
+    #[payable]
+    pub fn retrieve_funds(&mut self, quantity: U128) -> Promise {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(self.revenue_splits.is_empty(), ContractError::RevenueSplitsSet);
+        let amount = std::cmp::min(quantity.0, self.withdrawable_balance().0);
+        require(amount > 0, ContractError::NothingToWithdraw);
+
+        self.revenue_withdrawn += amount;
+        FundsWithdrawn {
+            beneficiary: &self.funds_beneficiary,
+            amount: U128(amount),
+            total_withdrawn: U128(self.revenue_withdrawn),
+        }.emit();
+        Promise::new(self.funds_beneficiary.clone()).transfer(amount)
+    }
+
+    //wallet receiving the mint revenue through retrieve_funds
+    #[payable]
+    pub fn set_funds_beneficiary(&mut self, account_id: ValidAccountId) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+
+        self.funds_beneficiary = account_id.into();
+        true
+    }
+
+    pub fn funds_beneficiary(&self) -> AccountId {
+        self.funds_beneficiary.clone()
+    }
+
+    //shares of the mint revenue in basis points, summing to 10000. An empty table goes back
+    //to retrieve_funds and the single funds beneficiary
+    #[payable]
+    pub fn set_revenue_splits(&mut self, splits: HashMap<ValidAccountId, u32>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        if !splits.is_empty() {
+            require(splits.len() <= MAX_REVENUE_SPLITS, ContractError::InvalidRevenueSplits("too many beneficiaries"));
+            require(splits.values().all(|share| *share > 0), ContractError::InvalidRevenueSplits("shares must be greater than 0"));
+            require(
+                splits.values().sum::<u32>() == TOTAL_BASIS_POINTS,
+                ContractError::InvalidRevenueSplits("shares must add up to 10000")
+            );
+        }
+
+        self.revenue_splits = splits.into_iter().map(|(account_id, share)| (account_id.into(), share)).collect();
+        true
+    }
+
+    pub fn revenue_splits(&self) -> HashMap<AccountId, u32> {
+        self.revenue_splits.clone()
+    }
+
+    //pays every beneficiary of the split table its share of the withdrawable revenue.
+    //anyone can trigger it, the rounding dust stays for the next distribution and the
+    //shares that could not be transferred are booked back by resolve_revenue_transfer
+    pub fn distribute_funds(&mut self) -> U128 {
+        require(!self.revenue_splits.is_empty(), ContractError::InvalidRevenueSplits("no split table"));
+        let available = self.withdrawable_balance().0;
+        require(available > 0, ContractError::NothingToWithdraw);
+
+        let mut distributed: u128 = 0;
+        for (beneficiary, share) in self.revenue_splits.clone() {
+            let amount = available * u128::from(share) / u128::from(TOTAL_BASIS_POINTS);
+            if amount == 0 {
+                continue;
+            }
+            distributed += amount;
+            self.revenue_withdrawn += amount;
+            let total = self.revenue_distributed.get(&beneficiary).unwrap_or(0);
+            self.revenue_distributed.insert(&beneficiary, &(total + amount));
+            FundsWithdrawn {
+                beneficiary: &beneficiary,
+                amount: U128(amount),
+                total_withdrawn: U128(self.revenue_withdrawn),
+            }.emit();
+            Promise::new(beneficiary.clone()).transfer(amount).then(ext_revenue::resolve_revenue_transfer(
+                beneficiary,
+                U128(amount),
+                &env::current_account_id(),
+                0,
+                GAS_FOR_RESOLVE_REVENUE_TRANSFER,
+            ));
+        }
+        U128(distributed)
+    }
+
+    #[private]
+    pub fn resolve_revenue_transfer(&mut self, beneficiary: AccountId, amount: U128) -> bool {
+        let transferred = matches!(env::promise_result(0), PromiseResult::Successful(_));
+        if !transferred {
+            self.revenue_withdrawn -= amount.0;
+            let total = self.revenue_distributed.get(&beneficiary).unwrap_or(0);
+            self.revenue_distributed.insert(&beneficiary, &total.saturating_sub(amount.0));
+        }
+        transferred
+    }
+
+    //mint revenue paid to account_id by distribute_funds so far
+    pub fn revenue_distributed(&self, account_id: ValidAccountId) -> U128 {
+        U128(self.revenue_distributed.get(account_id.as_ref()).unwrap_or(0))
+    }
+
+    //revenue not withdrawn yet, minus the pending auction rebates. never more than the balance
+    //above what the contract storage locks and the storage deposits of fungible token buyers
+    pub fn withdrawable_balance(&self) -> U128 {
+        let revenue = self.revenue_accrued
+            .saturating_sub(self.revenue_withdrawn)
+            .saturating_sub(self.rebate_liability());
+        let storage_reserve = Balance::from(env::storage_usage()) * env::storage_byte_cost();
+        let available = env::account_balance()
+            .saturating_sub(storage_reserve)
+            .saturating_sub(self.ft_storage_deposited);
+        U128(std::cmp::min(revenue, available))
+    }
+
+    pub fn revenue_ledger(&self) -> RevenueLedger {
+        RevenueLedger {
+            accrued: U128(self.revenue_accrued),
+            withdrawn: U128(self.revenue_withdrawn),
+            rebate_liability: U128(self.rebate_liability()),
+            withdrawable: self.withdrawable_balance(),
+        }
+    }
+}
+
+impl Contract {
+    //books the mint cost paid in NEAR
+    pub(crate) fn accrue_revenue(&mut self, amount: u128) {
+        self.revenue_accrued += amount;
+    }
+
+    //what auction buyers paid above the clearing price and didn't claim yet
+    fn rebate_liability(&self) -> u128 {
+        match &self.dutch_auction {
+            Some(auction) if auction.rebate => {
+                let clearing_price = if self.auction_settled() {
+                    self.auction_last_price.unwrap_or(auction.floor_price.0)
+                } else {
+                    auction.floor_price.0
+                };
+                self.auction_paid.saturating_sub(clearing_price * u128::from(self.auction_quantity))
+            }
+            _ => 0,
+        }
+    }
+}
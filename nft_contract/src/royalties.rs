@@ -0,0 +1,58 @@
+use crate::*;
+use near_sdk::Gas;
+use std::collections::HashMap;
+
+/// Gas left untouched by a migration page so the progress can still be saved.
+const GAS_RESERVED_FOR_MIGRATION: Gas = 20_000_000_000_000;
+
+#[near_bindgen]
+impl Contract {
+    //royalty receivers of every token without a royalty of its own. With `migrate`,
+    //migrate_royalties also drops the copies of the replaced royalties stored per token by older versions
+    #[payable]
+    pub fn set_perpetual_royalties(&mut self, royalties: HashMap<AccountId, u128>, migrate: bool) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+
+        let replaced = std::mem::replace(&mut self.tokens.default_royalty, HashMap::new());
+        self.tokens.set_default_royalty(royalties);
+        if migrate {
+            self.royalty_migration_cursor = 1;
+            self.royalty_migration_from = replaced;
+        } else {
+            self.royalty_migration_cursor = 0;
+            self.royalty_migration_from = HashMap::new();
+        }
+        true
+    }
+
+    pub fn perpetual_royalties(&self) -> HashMap<AccountId, u128> {
+        self.tokens.default_royalty.clone()
+    }
+
+    //removes the royalties stored per token that are copies of the replaced perpetual ones, so these
+    //tokens follow the new ones, one gas bounded page per call, until it returns true. Tokens minted
+    //with a royalty of their own keep it. The released storage was paid by the minters, it stays
+    //in the contract and is not refunded to the owner
+    #[payable]
+    pub fn migrate_royalties(&mut self) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+
+        while self.royalty_migration_cursor > 0
+            && self.royalty_migration_cursor <= self.max_supply
+            && env::prepaid_gas() - env::used_gas() > GAS_RESERVED_FOR_MIGRATION
+        {
+            let token_id = self.royalty_migration_cursor.to_string();
+            let royalties_by_id = self.tokens.royalties_by_id
+                .as_mut()
+                .unwrap_or_else(|| ContractError::MissingState("the token royalties").panic());
+            if royalties_by_id.get(&token_id).as_ref() == Some(&self.royalty_migration_from) {
+                royalties_by_id.remove(&token_id);
+            }
+            self.royalty_migration_cursor += 1;
+        }
+
+        self.royalty_migration_cursor == 0 || self.royalty_migration_cursor > self.max_supply
+    }
+}
@@ -0,0 +1,162 @@
+use crate::*;
+use near_sdk::json_types::{U128, U64};
+
+/// One step of the sale schedule, active while `start <= block_timestamp < end`.
+/// Timestamps are in nanoseconds, `price` defaults to `mint_cost` when not given.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SalePhase {
+    pub name: String,
+    pub start: U64,
+    pub end: U64,
+    pub price: Option<U128>,
+    pub per_wallet_cap: Option<u32>,
+    pub whitelist_only: bool,
+}
+
+/// Bounds applied to mints outside whitelist only phases, `None` means unbounded.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PublicMintLimits {
+    pub per_transaction: Option<u32>,
+    pub per_account: Option<u32>,
+}
+
+impl SalePhase {
+    fn is_active(&self, now: u64) -> bool {
+        self.start.0 <= now && now < self.end.0
+    }
+
+    fn overlaps(&self, other: &SalePhase) -> bool {
+        self.start.0 < other.end.0 && other.start.0 < self.end.0
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    //adds a phase to the schedule, or replaces the phase with the same name if it didn't start yet
+    #[payable]
+    pub fn set_sale_phase(&mut self, phase: SalePhase) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        let now = env::block_timestamp();
+        require(!phase.name.is_empty(), ContractError::InvalidSalePhase("name cannot be empty"));
+        require(phase.start.0 < phase.end.0, ContractError::InvalidSalePhase("must start before it ends"));
+        require(phase.start.0 > now, ContractError::InvalidSalePhase("must start in the future"));
+
+        self.remove_future_phase(&phase.name);
+        require(self.sale_phases.iter().all(|other| !other.overlaps(&phase)), ContractError::SalePhaseOverlap);
+        let position = self.sale_phases.iter().position(|other| other.start.0 > phase.start.0)
+            .unwrap_or(self.sale_phases.len());
+        self.sale_phases.insert(position, phase);
+        true
+    }
+
+    //removes a phase that didn't start yet
+    #[payable]
+    pub fn remove_sale_phase(&mut self, name: String) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        require(self.remove_future_phase(&name), ContractError::SalePhaseNotFound);
+        true
+    }
+
+    pub fn sale_phases(&self) -> Vec<SalePhase> {
+        self.sale_phases.clone()
+    }
+
+    pub fn current_phase(&self) -> Option<SalePhase> {
+        let now = env::block_timestamp();
+        self.sale_phases.iter().find(|phase| phase.is_active(now)).cloned()
+    }
+
+    #[payable]
+    pub fn set_public_mint_limits(&mut self, limits: PublicMintLimits) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+
+        self.public_mint_limits = limits;
+        true
+    }
+
+    pub fn public_mint_limits(&self) -> PublicMintLimits {
+        self.public_mint_limits.clone()
+    }
+
+    //number of NFTs minted by account_id over all phases
+    pub fn minted_by(&self, account_id: ValidAccountId) -> u32 {
+        self.minted_by_account.get(account_id.as_ref()).unwrap_or(0)
+    }
+}
+
+impl Contract {
+    //drops the phase called `name`, panics if it already started
+    fn remove_future_phase(&mut self, name: &str) -> bool {
+        let now = env::block_timestamp();
+        match self.sale_phases.iter().position(|phase| phase.name == name) {
+            Some(position) => {
+                require(self.sale_phases[position].start.0 > now, ContractError::SalePhaseStarted);
+                self.sale_phases.remove(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    //checks the active phase allows this mint and returns the price of one token,
+    //and whether that price comes from the dutch auction
+    pub(crate) fn assert_phase_allows_mint(
+        &mut self,
+        account_id: &AccountId,
+        quantity: u128,
+        allowlist: Option<&AllowlistProof>
+    ) -> (u128, bool) {
+        let now = env::block_timestamp();
+        let phase = self.current_phase().unwrap_or_else(|| ContractError::NoActivePhase.panic());
+
+        if let (true, Some(allowlist)) = (phase.whitelist_only, allowlist) {
+            self.debit_allowlist(account_id, allowlist, quantity);
+        } else if phase.whitelist_only {
+            let allowance: u128 = self.whitelist.get(account_id).unwrap_or(0);
+            require(allowance >= quantity, ContractError::WhitelistAllowanceExceeded);
+            self.whitelist.insert(account_id, &(allowance - quantity));
+        } else {
+            if let Some(per_transaction) = self.public_mint_limits.per_transaction {
+                require(quantity <= u128::from(per_transaction), ContractError::TransactionLimitExceeded);
+            }
+            if let Some(per_account) = self.public_mint_limits.per_account {
+                let minted = u128::from(self.minted_by_account.get(account_id).unwrap_or(0));
+                require(minted + quantity <= u128::from(per_account), ContractError::AccountLimitExceeded);
+            }
+        }
+
+        if let Some(cap) = phase.per_wallet_cap {
+            let key = (phase.name.clone(), account_id.clone());
+            let minted = u128::from(self.phase_minted.get(&key).unwrap_or(0));
+            require(minted + quantity <= u128::from(cap), ContractError::PhaseCapExceeded);
+            self.phase_minted.insert(&key, &((minted + quantity) as u32));
+        }
+
+        let from_auction = self.dutch_auction.is_some() && !phase.whitelist_only;
+        (self.phase_price(&phase, now), from_auction)
+    }
+
+    //adds to the lifetime counter of account_id
+    pub(crate) fn record_minted(&mut self, account_id: &AccountId, quantity: u32) {
+        let minted = self.minted_by_account.get(account_id).unwrap_or(0);
+        self.minted_by_account.insert(account_id, &(minted + quantity));
+    }
+
+    //any phase of the schedule started
+    pub(crate) fn any_phase_started(&self) -> bool {
+        let now = env::block_timestamp();
+        self.sale_phases.iter().any(|phase| phase.start.0 <= now)
+    }
+
+    //the collection sold out, or the sale started and was then locked or ran past its last phase
+    pub(crate) fn sale_ended(&self) -> bool {
+        let now = env::block_timestamp();
+        let schedule_over = !self.sale_phases.is_empty() && self.sale_phases.iter().all(|phase| phase.end.0 <= now);
+        self.sold_out() || (self.sale_started() && (self.sales_locked || schedule_over))
+    }
+}
@@ -0,0 +1,70 @@
+//! Contract and call context shared by the unit tests of the contract modules.
+
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::test_utils::{accounts, VMContextBuilder};
+use near_sdk::{testing_env, MockedBlockchain};
+
+/// Call made by the owner, `accounts(1)`, to the contract deployed at `accounts(0)`,
+/// with the yocto owner methods require.
+pub fn owner_context() -> VMContextBuilder {
+    let mut builder = VMContextBuilder::new();
+    builder
+        .current_account_id(accounts(0))
+        .signer_account_id(accounts(1))
+        .predecessor_account_id(accounts(1))
+        .attached_deposit(1);
+    builder
+}
+
+/// Contract owned by `accounts(1)` with `max_supply` ids, set up from `owner_context`.
+pub fn new_contract(max_supply: u64) -> Contract {
+    testing_env!(owner_context().build());
+    Contract::new(
+        accounts(1),
+        NFTContractMetadata {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: "Tokonami".to_string(),
+            symbol: "TOKONAMI".to_string(),
+            icon: None,
+            base_uri: None,
+            reference: None,
+            reference_hash: None,
+        },
+        U128(10),
+        accounts(2).to_string(),
+        U128(500),
+        max_supply,
+        "Tokonami #{id}".to_string(),
+        "Tokonami test collection".to_string(),
+        Base64VecU8(env::sha256(&[0u8; SEED_LEN])),
+        "media".to_string(),
+        "reference".to_string()
+    )
+}
+
+/// Takes every id left in the random pool, choosing each slot with `pick(draw, remaining)`,
+/// checks no swap is left behind and returns the ids taken, sorted.
+pub fn drain_pool(contract: &mut Contract, pick: impl Fn(u64, u64) -> u64) -> Vec<u64> {
+    let mut ids = Vec::new();
+    let mut draw = 0;
+    while contract.pool_remaining > 0 {
+        let slot = pick(draw, contract.pool_remaining);
+        ids.push(contract.pool_take(slot));
+        draw += 1;
+    }
+    for slot in 0..contract.max_supply {
+        assert!(contract.pool_swaps.get(&slot).is_none(), "slot {} still swapped", slot);
+    }
+    ids.sort_unstable();
+    ids
+}
+
+/// Slot choices for `drain_pool`: always the first slot, always the last one, and a spread.
+pub fn slot_pickers() -> Vec<Box<dyn Fn(u64, u64) -> u64>> {
+    vec![
+        Box::new(|_, _| 0),
+        Box::new(|_, remaining| remaining - 1),
+        Box::new(|draw, remaining| (draw * 7 + 3) % remaining),
+    ]
+}
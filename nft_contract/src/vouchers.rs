@@ -0,0 +1,172 @@
+use crate::*;
+use ed25519_dalek::{PublicKey, Signature, Verifier};
+use near_sdk::json_types::{U128, U64};
+use std::convert::TryFrom;
+
+/// Prefix of every signed voucher, keeps the key from signing anything else by mistake.
+pub const VOUCHER_DOMAIN: &[u8] = b"tokonami-voucher";
+
+/// Mint authorization signed off chain by the voucher key. `account_id` mints up to `quantity`
+/// NFTs at `price` each, before `expires_at` (nanoseconds). Each nonce can only be used once.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Voucher {
+    pub account_id: AccountId,
+    pub quantity: u32,
+    pub price: U128,
+    pub nonce: u64,
+    pub expires_at: U64,
+}
+
+/// Bytes signed by the voucher key: `VOUCHER_DOMAIN`, then the borsh serialized contract
+/// account id and voucher, so a voucher is only valid on the contract it was issued for.
+pub fn voucher_message(contract_id: &AccountId, voucher: &Voucher) -> Vec<u8> {
+    let mut message = VOUCHER_DOMAIN.to_vec();
+    message.extend(contract_id.try_to_vec().unwrap());
+    message.extend(voucher.try_to_vec().unwrap());
+    message
+}
+
+#[near_bindgen]
+impl Contract {
+    //registers the ed25519 public key signing the vouchers, None stops accepting them
+    #[payable]
+    pub fn set_voucher_key(&mut self, public_key: Option<Base64VecU8>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        if let Some(public_key) = &public_key {
+            require(PublicKey::from_bytes(&public_key.0).is_ok(), ContractError::InvalidVoucherKey);
+        }
+
+        self.voucher_key = public_key.map(|public_key| public_key.0);
+        true
+    }
+
+    pub fn voucher_key(&self) -> Option<Base64VecU8> {
+        self.voucher_key.clone().map(Base64VecU8)
+    }
+
+    pub fn is_voucher_used(&self, nonce: u64) -> bool {
+        self.used_voucher_nonces.contains(&nonce)
+    }
+
+    //mints with a voucher instead of the sale phases, the voucher sets the price and quantity.
+    //like mint lists, these mints don't count against the public limits. Like nft_mint, mints
+    //what is left when the pool runs low and refunds the rest
+    #[payable]
+    pub fn nft_mint_with_voucher(
+        &mut self,
+        voucher: Voucher,
+        signature: Base64VecU8,
+        receiver_id: Option<ValidAccountId>
+    ) -> MintResult {
+        let account_id: AccountId = env::predecessor_account_id();
+        let receiver_id: AccountId = receiver_id.map(|receiver_id| receiver_id.into()).unwrap_or_else(|| account_id.clone());
+
+        require(!self.sales_locked, ContractError::SalesLocked);
+        self.use_voucher(&account_id, &voucher, &signature.0);
+        let requested = u128::from(voucher.quantity);
+        let filled = self.fillable(requested);
+        let price = voucher.price.0;
+
+        let initial_storage_usage = env::storage_usage();
+        let memo = if receiver_id != account_id { Some(format!("paid by {}", account_id)) } else { None };
+        let tokens = self.internal_mint_random(&account_id, &receiver_id, filled, price, memo);
+        self.accrue_revenue(price * filled);
+        refund_deposit_mint(env::storage_usage().saturating_sub(initial_storage_usage), price * filled);
+        MintResult { tokens, requested: U128(requested), filled: U128(filled) }
+    }
+}
+
+impl Contract {
+    //checks the voucher was signed for account_id and is still valid, then burns its nonce
+    fn use_voucher(&mut self, account_id: &AccountId, voucher: &Voucher, signature: &[u8]) {
+        let key = self.voucher_key.as_ref().unwrap_or_else(|| ContractError::NoVoucherKey.panic());
+        require(&voucher.account_id == account_id, ContractError::VoucherAccountMismatch);
+        require(env::block_timestamp() < voucher.expires_at.0, ContractError::VoucherExpired);
+        require(!self.used_voucher_nonces.contains(&voucher.nonce), ContractError::VoucherUsed);
+
+        let public_key = PublicKey::from_bytes(key).unwrap_or_else(|_| ContractError::InvalidVoucherKey.panic());
+        let signature = Signature::try_from(signature).unwrap_or_else(|_| ContractError::InvalidVoucherSignature.panic());
+        let message = voucher_message(&env::current_account_id(), voucher);
+        require(public_key.verify(&message, &signature).is_ok(), ContractError::InvalidVoucherSignature);
+
+        self.used_voucher_nonces.insert(&voucher.nonce);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::test_utils::{new_contract, owner_context};
+    use ed25519_dalek::{Keypair, SecretKey, Signer};
+    use near_sdk::test_utils::accounts;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    const NOW: u64 = 1_000_000;
+
+    fn keypair() -> Keypair {
+        let secret = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+        let public = PublicKey::from(&secret);
+        Keypair { secret, public }
+    }
+
+    fn voucher() -> Voucher {
+        Voucher { account_id: accounts(3).to_string(), quantity: 2, price: U128(5), nonce: 1, expires_at: U64(NOW + 1) }
+    }
+
+    fn sign(contract_id: &str, voucher: &Voucher) -> Vec<u8> {
+        keypair().sign(&voucher_message(&contract_id.to_string(), voucher)).to_bytes().to_vec()
+    }
+
+    //contract trusting the test key, then called by the voucher holder at NOW
+    fn setup() -> Contract {
+        let mut contract = new_contract(10);
+        contract.set_voucher_key(Some(Base64VecU8(keypair().public.to_bytes().to_vec())));
+        testing_env!(owner_context().predecessor_account_id(accounts(3)).block_timestamp(NOW).build());
+        contract
+    }
+
+    #[test]
+    fn valid_voucher_burns_its_nonce() {
+        let mut contract = setup();
+        let voucher = voucher();
+        contract.use_voucher(&accounts(3).to_string(), &voucher, &sign(accounts(0).as_ref(), &voucher));
+        assert!(contract.is_voucher_used(voucher.nonce));
+    }
+
+    #[test]
+    #[should_panic(expected = "E802")]
+    fn voucher_for_another_contract_fails() {
+        let mut contract = setup();
+        let voucher = voucher();
+        contract.use_voucher(&accounts(3).to_string(), &voucher, &sign("other.near", &voucher));
+    }
+
+    #[test]
+    #[should_panic(expected = "E803")]
+    fn expired_voucher_fails() {
+        let mut contract = setup();
+        let voucher = Voucher { expires_at: U64(NOW), ..voucher() };
+        contract.use_voucher(&accounts(3).to_string(), &voucher, &sign(accounts(0).as_ref(), &voucher));
+    }
+
+    #[test]
+    #[should_panic(expected = "E804")]
+    fn reused_nonce_fails() {
+        let mut contract = setup();
+        let voucher = voucher();
+        let signature = sign(accounts(0).as_ref(), &voucher);
+        contract.use_voucher(&accounts(3).to_string(), &voucher, &signature);
+        contract.use_voucher(&accounts(3).to_string(), &voucher, &signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "E802")]
+    fn tampered_price_fails() {
+        let mut contract = setup();
+        let signature = sign(accounts(0).as_ref(), &voucher());
+        let voucher = Voucher { price: U128(1), ..voucher() };
+        contract.use_voucher(&accounts(3).to_string(), &voucher, &signature);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::utils::refund_deposit;
+use near_sdk::json_types::U128;
+use near_sdk::Balance;
+use std::collections::HashMap;
+
+#[near_bindgen]
+impl Contract {
+    //sets the allowance of each account, overwriting the previous one.
+    //the owner pays for the storage of new entries, the surplus is refunded
+    #[payable]
+    pub fn add_to_whitelist(
+        &mut self,
+        whitelist_map: HashMap<AccountId, u128>
+    ) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        let initial_storage_usage = env::storage_usage();
+        for (account_id, allowance) in whitelist_map {
+            self.whitelist.insert(&account_id, &allowance);
+        }
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+        true
+    }
+
+    //adds to the allowance of each account instead of overwriting it
+    #[payable]
+    pub fn increase_allowance(
+        &mut self,
+        whitelist_map: HashMap<AccountId, u128>
+    ) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        let initial_storage_usage = env::storage_usage();
+        for (account_id, increase) in whitelist_map {
+            let allowance = self.whitelist.get(&account_id).unwrap_or(0);
+            self.whitelist.insert(&account_id, &(allowance + increase));
+        }
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+        true
+    }
+
+    //drops the accounts from the whitelist, the storage they used is refunded to the owner
+    #[payable]
+    pub fn remove_from_whitelist(&mut self, account_ids: Vec<AccountId>) -> bool {
+        require(env::predecessor_account_id() == self.tokens.owner_id, ContractError::Unauthorized);
+        assert_one_or_more_yocto();
+        let initial_storage_usage = env::storage_usage();
+        for account_id in account_ids {
+            self.whitelist.remove(&account_id);
+        }
+        let released = initial_storage_usage.saturating_sub(env::storage_usage());
+        if released > 0 {
+            Promise::new(self.tokens.owner_id.clone()).transfer(Balance::from(released) * env::storage_byte_cost());
+        }
+        true
+    }
+
+    //allowance left to account_id
+    pub fn is_whitelist(
+        &self,
+        account_id: AccountId
+    ) -> u128 {
+        self.whitelist.get(&account_id).unwrap_or(0)
+    }
+
+    pub fn whitelist_len(&self) -> u64 {
+        self.whitelist.len()
+    }
+
+    //whitelisted accounts and their allowance, paginated like nft_tokens
+    pub fn whitelist_entries(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<(AccountId, u128)> {
+        let start = from_index.map(|index| index.0).unwrap_or(0);
+        let limit = limit.unwrap_or(u64::MAX);
+        require(limit != 0, ContractError::ZeroLimit);
+        let keys = self.whitelist.keys_as_vector();
+        let values = self.whitelist.values_as_vector();
+        (start..std::cmp::min(start.saturating_add(u128::from(limit)), u128::from(keys.len())))
+            .map(|index| {
+                let entry = keys.get(index as u64).zip(values.get(index as u64));
+                entry.unwrap_or_else(|| ContractError::MissingState("a whitelist entry").panic())
+            })
+            .collect()
+    }
+}
@@ -19,6 +19,9 @@ const DATA_IMAGE_SVG_NEAR_ICON: &str = "data:image/svg+xml,%3Csvg xmlns='http://
 
 const GAS_ATTACHMENT: u64 = 300_000_000_000_000;
 const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+//seed used by the tests and its sha256 commitment, both base64 encoded
+const SEED: &str = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+const SEED_COMMITMENT: &str = "Zmh6rfhivXdsj8GLjp+OIAiXFIVu4jOzkCpZHQ1fKSU=";
 
 pub fn should_fail(r: ExecutionResult) {
     match r.status() {
@@ -78,6 +81,10 @@ fn simulate_full_flow() {
             "mint_cost": U128(ONE_NEAR),
             "royalties_account": dev_account.account_id(),
             "royalties_value": U128(500),
+            "max_supply": 2331,
+            "title_template": "Tokonami #{id}".to_string(),
+            "description": "2331 TOKONAMI Ready for the Revolution".to_string(),
+            "seed_commitment": SEED_COMMITMENT,
             "url_media_base": "test".to_string(), 
             "url_reference_base": "test".to_string()
         }).to_string().into_bytes(),
@@ -118,18 +125,6 @@ fn simulate_full_flow() {
     //     1
     // ).assert_success();
     
-    let mut result: bool = false;
-    while !result {
-        result = dev_account.call(
-            nft_account.account_id(), 
-            "initilize_random_generator",
-            &json!({
-            }).to_string().into_bytes(),
-            GAS_ATTACHMENT, 
-            0
-        ).unwrap_json();
-    }
-    
     let whitelist_hashmap = HashMap::from([(&consumer1.account_id, 3000), (&consumer2.account_id, 3000)]);
 
     dev_account.call(
@@ -139,14 +134,23 @@ fn simulate_full_flow() {
             "whitelist_map": whitelist_hashmap
         }).to_string().into_bytes(),
         GAS_ATTACHMENT, 
-        1
+        to_yocto("1")
     ).assert_success();
 
+    //open a whitelist phase right away for as long as the test runs
+    let now = root.borrow_runtime().current_block().block_timestamp;
     dev_account.call(
         nft_account.account_id(), 
-        "unlock_sales",
+        "set_sale_phase",
         &json!({
-            "sales_lock": false
+            "phase": {
+                "name": "whitelist",
+                "start": U64(now + 1),
+                "end": U64(u64::MAX),
+                "price": null,
+                "per_wallet_cap": null,
+                "whitelist_only": true
+            }
         }).to_string().into_bytes(),
         GAS_ATTACHMENT, 
         1
@@ -192,6 +196,41 @@ fn simulate_full_flow() {
 
     assert_eq!(minted, "2331".to_string());
 
+    //only the owner can reveal the seed, and only the committed one
+    should_fail(
+        consumer1.call(
+            nft_account.account_id(), 
+            "reveal_seed",
+            &json!({
+                "seed": SEED
+            }).to_string().into_bytes(),
+            GAS_ATTACHMENT, 
+            1
+        )
+    );
+
+    should_fail(
+        dev_account.call(
+            nft_account.account_id(), 
+            "reveal_seed",
+            &json!({
+                "seed": SEED_COMMITMENT
+            }).to_string().into_bytes(),
+            GAS_ATTACHMENT, 
+            1
+        )
+    );
+
+    dev_account.call(
+        nft_account.account_id(), 
+        "reveal_seed",
+        &json!({
+            "seed": SEED
+        }).to_string().into_bytes(),
+        GAS_ATTACHMENT, 
+        1
+    ).assert_success();
+
     should_fail(
         consumer2.call(
             nft_account.account_id(), 
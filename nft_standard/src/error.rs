@@ -0,0 +1,347 @@
+//! Errors raised by the NFT standard and the Tokonami contract.
+//!
+//! Every failure panics with `E<code>: <message>`. Codes are stable: new errors get new codes
+//! and a retired code is never reused, so frontends and indexers can match on the code alone.
+//! Codes are grouped by area:
+//! * 1xx: caller, deposit and initialization
+//! * 2xx: tokens, approvals, enumeration and payouts
+//! * 3xx: collection setup and reserved ids
+//! * 4xx: sale schedule and mint limits
+//! * 5xx: dutch auction
+//! * 6xx: fungible token payments
+//! * 7xx: seed and metadata reveal
+//! * 8xx: signed mint vouchers
+//! * 9xx: mint revenue and withdrawals
+//!
+//! `MissingState` guards invariants of the contract state, v.g. an extension that is always
+//! enabled, and should never be seen. In the NFT standard, the only raw panics left are borsh
+//! and json encodings of in-memory values, which can't fail (v.g. the storage key of the owner
+//! set measured by `NonFungibleToken::new`). The `fungible_token` and `upgrade` modules keep the
+//! `env::panic` and `assert!` of the upstream standards: the Tokonami contract only uses the
+//! `FungibleTokenReceiver` trait, which doesn't panic.
+//!
+//! No code is retired yet, every variant below is raised somewhere.
+
+use near_sdk::{env, Balance};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractError {
+    Unauthorized,
+    RequiresOneYocto,
+    RequiresAttachedDeposit,
+    InsufficientDeposit { required: Balance },
+    AlreadyInitialized,
+    InvalidAccountId,
+    MissingState(&'static str),
+
+    TokenNotFound,
+    TokenAlreadyExists,
+    MetadataRequired,
+    SenderNotApproved,
+    ApprovalIdMismatch { actual: u64, given: u64 },
+    SameOwner,
+    TooManyRoyalties,
+    ApprovalNotSupported,
+    NotTokenOwner,
+    EnumerationNotSupported,
+    FromIndexOutOfBounds,
+    ZeroLimit,
+    TooManyPayoutReceivers,
+    InvalidMetadata(&'static str),
+    InvalidRoyalty(&'static str),
+    PayoutOverflow,
+
+    InvalidCollectionConfig(&'static str),
+    IndexOutOfRange,
+    InvalidCollectionIndex,
+    InvalidAssetHash,
+    InvalidProvenanceHash,
+    SaleAlreadyStarted,
+    TokenNotReserved,
+    IdNotInPool,
+
+    SalesLocked,
+    SoldOut,
+    NoActivePhase,
+    WhitelistAllowanceExceeded,
+    TransactionLimitExceeded,
+    AccountLimitExceeded,
+    PhaseCapExceeded,
+    InvalidSalePhase(&'static str),
+    SalePhaseOverlap,
+    SalePhaseNotFound,
+    SalePhaseStarted,
+    NoAllowlist,
+    InvalidAllowlistProof,
+    InvalidAllowlistRoot,
+    MintListNotFound,
+    NotInMintList,
+    MintListInactive,
+    MintListAllowanceExceeded,
+    InvalidMintList(&'static str),
+
+    InvalidAuction(&'static str),
+    NoAuction,
+    NoRebates,
+    AuctionNotSettled,
+    NoAuctionPurchase,
+
+    FtNotAccepted,
+    InvalidFtMsg,
+    InsufficientFtAmount { required: Balance },
+    InsufficientFtStorageDeposit { required: Balance },
+    FtMintListNotSupported,
+
+    SeedAlreadyRevealed,
+    SaleNotEnded,
+    SeedMismatch,
+    NotDelayedReveal,
+    ProvenanceMismatch,
+    SeedNotRevealed,
+    RevealParamsChanged,
+
+    NoVoucherKey,
+    InvalidVoucherKey,
+    InvalidVoucherSignature,
+    VoucherExpired,
+    VoucherUsed,
+    VoucherAccountMismatch,
+
+    NothingToWithdraw,
+    InvalidRevenueSplits(&'static str),
+    RevenueSplitsSet,
+}
+
+impl ContractError {
+    pub fn code(&self) -> u32 {
+        match self {
+            ContractError::Unauthorized => 100,
+            ContractError::RequiresOneYocto => 101,
+            ContractError::RequiresAttachedDeposit => 102,
+            ContractError::InsufficientDeposit { .. } => 103,
+            ContractError::AlreadyInitialized => 104,
+            ContractError::InvalidAccountId => 105,
+            ContractError::MissingState(_) => 106,
+
+            ContractError::TokenNotFound => 200,
+            ContractError::TokenAlreadyExists => 201,
+            ContractError::MetadataRequired => 202,
+            ContractError::SenderNotApproved => 203,
+            ContractError::ApprovalIdMismatch { .. } => 204,
+            ContractError::SameOwner => 205,
+            ContractError::TooManyRoyalties => 206,
+            ContractError::ApprovalNotSupported => 207,
+            ContractError::NotTokenOwner => 208,
+            ContractError::EnumerationNotSupported => 209,
+            ContractError::FromIndexOutOfBounds => 210,
+            ContractError::ZeroLimit => 211,
+            ContractError::TooManyPayoutReceivers => 212,
+            ContractError::InvalidMetadata(_) => 213,
+            ContractError::InvalidRoyalty(_) => 214,
+            ContractError::PayoutOverflow => 215,
+
+            ContractError::InvalidCollectionConfig(_) => 300,
+            ContractError::IndexOutOfRange => 301,
+            ContractError::InvalidCollectionIndex => 302,
+            ContractError::InvalidAssetHash => 303,
+            ContractError::InvalidProvenanceHash => 304,
+            ContractError::SaleAlreadyStarted => 305,
+            ContractError::TokenNotReserved => 306,
+            ContractError::IdNotInPool => 307,
+
+            ContractError::SalesLocked => 400,
+            ContractError::SoldOut => 401,
+            ContractError::NoActivePhase => 402,
+            ContractError::WhitelistAllowanceExceeded => 403,
+            ContractError::TransactionLimitExceeded => 404,
+            ContractError::AccountLimitExceeded => 405,
+            ContractError::PhaseCapExceeded => 406,
+            ContractError::InvalidSalePhase(_) => 407,
+            ContractError::SalePhaseOverlap => 408,
+            ContractError::SalePhaseNotFound => 409,
+            ContractError::SalePhaseStarted => 410,
+            ContractError::NoAllowlist => 411,
+            ContractError::InvalidAllowlistProof => 412,
+            ContractError::InvalidAllowlistRoot => 413,
+            ContractError::MintListNotFound => 414,
+            ContractError::NotInMintList => 415,
+            ContractError::MintListInactive => 416,
+            ContractError::MintListAllowanceExceeded => 417,
+            ContractError::InvalidMintList(_) => 418,
+
+            ContractError::InvalidAuction(_) => 500,
+            ContractError::NoAuction => 501,
+            ContractError::NoRebates => 502,
+            ContractError::AuctionNotSettled => 503,
+            ContractError::NoAuctionPurchase => 504,
+
+            ContractError::FtNotAccepted => 600,
+            ContractError::InvalidFtMsg => 601,
+            ContractError::InsufficientFtAmount { .. } => 602,
+            ContractError::InsufficientFtStorageDeposit { .. } => 603,
+            ContractError::FtMintListNotSupported => 604,
+
+            ContractError::SeedAlreadyRevealed => 700,
+            ContractError::SaleNotEnded => 701,
+            ContractError::SeedMismatch => 702,
+            ContractError::NotDelayedReveal => 703,
+            ContractError::ProvenanceMismatch => 704,
+            ContractError::SeedNotRevealed => 705,
+            ContractError::RevealParamsChanged => 706,
+
+            ContractError::NoVoucherKey => 800,
+            ContractError::InvalidVoucherKey => 801,
+            ContractError::InvalidVoucherSignature => 802,
+            ContractError::VoucherExpired => 803,
+            ContractError::VoucherUsed => 804,
+            ContractError::VoucherAccountMismatch => 805,
+
+            ContractError::NothingToWithdraw => 900,
+            ContractError::InvalidRevenueSplits(_) => 901,
+            ContractError::RevenueSplitsSet => 902,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ContractError::Unauthorized => "Unauthorized".to_string(),
+            ContractError::RequiresOneYocto => "Requires attached deposit of exactly 1 yoctoNEAR".to_string(),
+            ContractError::RequiresAttachedDeposit => "Requires attached deposit of 1 yoctoNEAR or more".to_string(),
+            ContractError::InsufficientDeposit { required } => {
+                format!("Must attach {} yoctoNEAR to cover the cost of this call", required)
+            }
+            ContractError::AlreadyInitialized => "Already initialized".to_string(),
+            ContractError::InvalidAccountId => "Invalid account id".to_string(),
+            ContractError::MissingState(what) => format!("Contract state is missing {}", what),
+
+            ContractError::TokenNotFound => "Token not found".to_string(),
+            ContractError::TokenAlreadyExists => "token_id must be unique".to_string(),
+            ContractError::MetadataRequired => "Must provide metadata".to_string(),
+            ContractError::SenderNotApproved => "Sender not approved".to_string(),
+            ContractError::ApprovalIdMismatch { actual, given } => format!(
+                "The actual approval_id {} is different from the given approval_id {}",
+                actual, given
+            ),
+            ContractError::SameOwner => "Current and next owner must differ".to_string(),
+            ContractError::TooManyRoyalties => "Cannot add more than 6 perpetual royalty amounts".to_string(),
+            ContractError::ApprovalNotSupported => "NFT does not support Approval Management".to_string(),
+            ContractError::NotTokenOwner => "Predecessor must be token owner".to_string(),
+            ContractError::EnumerationNotSupported => "NFT does not support Enumeration".to_string(),
+            ContractError::FromIndexOutOfBounds => "Out of bounds, please use a smaller from_index".to_string(),
+            ContractError::ZeroLimit => "Cannot provide limit of 0".to_string(),
+            ContractError::TooManyPayoutReceivers => "Market cannot payout to that many receivers".to_string(),
+            ContractError::InvalidMetadata(reason) => format!("Invalid metadata: {}", reason),
+            ContractError::InvalidRoyalty(reason) => format!("Invalid royalty: {}", reason),
+            ContractError::PayoutOverflow => "Balance is too large to compute the payout".to_string(),
+
+            ContractError::InvalidCollectionConfig(reason) => format!("Invalid collection config: {}", reason),
+            ContractError::IndexOutOfRange => "Collection index out of range".to_string(),
+            ContractError::InvalidCollectionIndex => "Token id must be a collection index".to_string(),
+            ContractError::InvalidAssetHash => "Asset hashes have to be 32 bytes".to_string(),
+            ContractError::InvalidProvenanceHash => {
+                "Provenance hash has to be a lowercase hex encoded sha256 hash".to_string()
+            }
+            ContractError::SaleAlreadyStarted => "Cannot be changed after the sale started".to_string(),
+            ContractError::TokenNotReserved => "Token id is not reserved".to_string(),
+            ContractError::IdNotInPool => "Id is not in the random pool".to_string(),
+
+            ContractError::SalesLocked => "sales locked".to_string(),
+            ContractError::SoldOut => "sold out".to_string(),
+            ContractError::NoActivePhase => "No sale phase is active".to_string(),
+            ContractError::WhitelistAllowanceExceeded => {
+                "Whitelist error: this account has no allowance for minting this amount of NFTs".to_string()
+            }
+            ContractError::TransactionLimitExceeded => {
+                "Public sale error: cannot mint this amount of NFTs in one transaction".to_string()
+            }
+            ContractError::AccountLimitExceeded => "Public sale error: this account cannot mint more NFTs".to_string(),
+            ContractError::PhaseCapExceeded => {
+                "Sale phase error: this account cannot mint more NFTs in this phase".to_string()
+            }
+            ContractError::InvalidSalePhase(reason) => format!("Invalid sale phase: {}", reason),
+            ContractError::SalePhaseOverlap => "Sale phase overlaps with another phase".to_string(),
+            ContractError::SalePhaseNotFound => "Sale phase not found".to_string(),
+            ContractError::SalePhaseStarted => "Sale phase already started".to_string(),
+            ContractError::NoAllowlist => "No allowlist root is set".to_string(),
+            ContractError::InvalidAllowlistProof => "Allowlist proof does not match the allowlist root".to_string(),
+            ContractError::InvalidAllowlistRoot => "Allowlist root has to be 32 bytes".to_string(),
+            ContractError::MintListNotFound => "Mint list not found".to_string(),
+            ContractError::NotInMintList => "This account is not in the mint list".to_string(),
+            ContractError::MintListInactive => "Mint list is not active".to_string(),
+            ContractError::MintListAllowanceExceeded => {
+                "Mint list error: this account cannot mint more NFTs from this list".to_string()
+            }
+            ContractError::InvalidMintList(reason) => format!("Invalid mint list: {}", reason),
+
+            ContractError::InvalidAuction(reason) => format!("Invalid dutch auction: {}", reason),
+            ContractError::NoAuction => "No dutch auction".to_string(),
+            ContractError::NoRebates => "Auction has no rebates".to_string(),
+            ContractError::AuctionNotSettled => "Auction is not settled yet".to_string(),
+            ContractError::NoAuctionPurchase => "No auction purchase".to_string(),
+
+            ContractError::FtNotAccepted => "Fungible token not accepted as payment".to_string(),
+            ContractError::InvalidFtMsg => "msg must be {\"quantity\": <quantity>}".to_string(),
+            ContractError::InsufficientFtAmount { required } => {
+                format!("Must transfer {} tokens to cover the mint cost", required)
+            }
+            ContractError::InsufficientFtStorageDeposit { required } => {
+                format!("Must deposit {} yoctoNEAR with ft_mint_storage_deposit to cover the storage of the NFTs", required)
+            }
+            ContractError::FtMintListNotSupported => "Mint lists are paid in NEAR with nft_mint".to_string(),
+
+            ContractError::SeedAlreadyRevealed => "Seed already revealed".to_string(),
+            ContractError::SaleNotEnded => "Seed can only be revealed once the collection sold out or the sale ended".to_string(),
+            ContractError::SeedMismatch => "Seed does not match commitment".to_string(),
+            ContractError::NotDelayedReveal => "Collection is not in delayed reveal mode".to_string(),
+            ContractError::ProvenanceMismatch => "Provenance hash does not match the registered one".to_string(),
+            ContractError::SeedNotRevealed => "Seed must be revealed before the collection".to_string(),
+            ContractError::RevealParamsChanged => {
+                "Reveal parameters differ from the ones of the first call".to_string()
+            }
+
+            ContractError::NoVoucherKey => "No voucher key is set".to_string(),
+            ContractError::InvalidVoucherKey => "Voucher key has to be a 32 bytes ed25519 public key".to_string(),
+            ContractError::InvalidVoucherSignature => "Voucher signature is not valid".to_string(),
+            ContractError::VoucherExpired => "Voucher expired".to_string(),
+            ContractError::VoucherUsed => "Voucher nonce already used".to_string(),
+            ContractError::VoucherAccountMismatch => "Voucher was issued to another account".to_string(),
+
+            ContractError::NothingToWithdraw => "No mint revenue to withdraw".to_string(),
+            ContractError::InvalidRevenueSplits(reason) => format!("Invalid revenue splits: {}", reason),
+            ContractError::RevenueSplitsSet => "Mint revenue is paid through distribute_funds".to_string(),
+        }
+    }
+
+    /// Aborts the call with this error.
+    pub fn panic(&self) -> ! {
+        env::panic(self.to_string().as_bytes())
+    }
+}
+
+impl fmt::Display for ContractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "E{}: {}", self.code(), self.message())
+    }
+}
+
+/// Aborts the call with `error` unless `condition` holds.
+pub fn require(condition: bool, error: ContractError) {
+    if !condition {
+        error.panic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_prefixes_code() {
+        assert_eq!(ContractError::Unauthorized.to_string(), "E100: Unauthorized");
+        assert_eq!(
+            ContractError::ApprovalIdMismatch { actual: 2, given: 1 }.to_string(),
+            "E204: The actual approval_id 2 is different from the given approval_id 1"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+/// Errors with stable codes, shared by the standard and the contracts built on it.
+pub mod error;
 /// Fungible tokens as described in [by the spec](https://nomicon.io/Standards/FungibleToken/README.html).
 pub mod fungible_token;
 /// Non-fungible tokens as described in [by the spec](https://nomicon.io/Standards/NonFungibleToken/README.html).
@@ -1,14 +1,15 @@
 /// Common implementation of the [approval management standard](https://nomicon.io/Standards/NonFungibleToken/ApprovalManagement.html) for NFTs.
 /// on the contract/account that has just been approved. This is not required to implement.
+use crate::error::{require, ContractError};
 use crate::non_fungible_token::approval::NonFungibleTokenApproval;
 use crate::non_fungible_token::token::TokenId;
 use crate::non_fungible_token::utils::{
-    assert_at_least_one_yocto, bytes_for_approved_account_id, refund_approved_account_ids,
-    refund_approved_account_ids_iter, refund_deposit,
+    assert_at_least_one_yocto, assert_one_yocto, bytes_for_approved_account_id,
+    refund_approved_account_ids, refund_approved_account_ids_iter, refund_deposit,
 };
 use crate::non_fungible_token::NonFungibleToken;
 use near_sdk::json_types::ValidAccountId;
-use near_sdk::{assert_one_yocto, env, ext_contract, AccountId, Balance, Gas, Promise};
+use near_sdk::{env, ext_contract, AccountId, Balance, Gas, Promise};
 use std::collections::HashMap;
 
 const GAS_FOR_NFT_APPROVE: Gas = 10_000_000_000_000;
@@ -34,29 +35,33 @@ impl NonFungibleTokenApproval for NonFungibleToken {
     ) -> Option<Promise> {
         assert_at_least_one_yocto();
         if self.approvals_by_id.is_none() {
-            env::panic(b"NFT does not support Approval Management");
+            ContractError::ApprovalNotSupported.panic()
         }
 
-        let owner_id = self.owner_by_id.get(&token_id).expect("Token not found");
+        let owner_id = self.owner_by_id.get(&token_id).unwrap_or_else(|| ContractError::TokenNotFound.panic());
 
-        assert_eq!(&env::predecessor_account_id(), &owner_id, "Predecessor must be token owner.");
+        require(env::predecessor_account_id() == owner_id, ContractError::NotTokenOwner);
 
         // get contract-level LookupMap of token_id to approvals HashMap
-        let approvals_by_id = self.approvals_by_id.as_mut().unwrap();
+        let approvals_by_id = self.approvals_by_id
+            .as_mut()
+            .unwrap_or_else(|| ContractError::ApprovalNotSupported.panic());
 
         // update HashMap of approvals for this token
         let approved_account_ids =
             &mut approvals_by_id.get(&token_id).unwrap_or_else(|| HashMap::new());
         let account_id: AccountId = account_id.into();
-        let approval_id: u64 =
-            self.next_approval_id_by_id.as_ref().unwrap().get(&token_id).unwrap_or_else(|| 1u64);
+        let next_approval_id_by_id = self.next_approval_id_by_id
+            .as_mut()
+            .unwrap_or_else(|| ContractError::MissingState("the next approval ids").panic());
+        let approval_id: u64 = next_approval_id_by_id.get(&token_id).unwrap_or_else(|| 1u64);
         let old_approval_id = approved_account_ids.insert(account_id.clone(), approval_id);
 
         // save updated approvals HashMap to contract's LookupMap
         approvals_by_id.insert(&token_id, &approved_account_ids);
 
         // increment next_approval_id for this token
-        self.next_approval_id_by_id.as_mut().unwrap().insert(&token_id, &(approval_id + 1));
+        next_approval_id_by_id.insert(&token_id, &(approval_id + 1));
 
         // If this approval replaced existing for same account, no storage was used.
         // Otherwise, require that enough deposit was attached to pay for storage, and refund
@@ -84,18 +89,19 @@ impl NonFungibleTokenApproval for NonFungibleToken {
     fn nft_revoke(&mut self, token_id: TokenId, account_id: ValidAccountId) {
         assert_one_yocto();
         if self.approvals_by_id.is_none() {
-            env::panic(b"NFT does not support Approval Management");
+            ContractError::ApprovalNotSupported.panic()
         }
 
-        let owner_id = self.owner_by_id.get(&token_id).expect("Token not found");
+        let owner_id = self.owner_by_id.get(&token_id).unwrap_or_else(|| ContractError::TokenNotFound.panic());
         let predecessor_account_id = env::predecessor_account_id();
 
-        assert_eq!(&predecessor_account_id, &owner_id, "Predecessor must be token owner.");
+        require(predecessor_account_id == owner_id, ContractError::NotTokenOwner);
 
+        let approvals_by_id = self.approvals_by_id
+            .as_mut()
+            .unwrap_or_else(|| ContractError::ApprovalNotSupported.panic());
         // if token has no approvals, do nothing
-        if let Some(approved_account_ids) =
-            &mut self.approvals_by_id.as_mut().unwrap().get(&token_id)
-        {
+        if let Some(approved_account_ids) = &mut approvals_by_id.get(&token_id) {
             // if account_id was already not approved, do nothing
             if approved_account_ids.remove(account_id.as_ref()).is_some() {
                 refund_approved_account_ids_iter(
@@ -104,10 +110,10 @@ impl NonFungibleTokenApproval for NonFungibleToken {
                 );
                 // if this was the last approval, remove the whole HashMap to save space.
                 if approved_account_ids.is_empty() {
-                    self.approvals_by_id.as_mut().unwrap().remove(&token_id);
+                    approvals_by_id.remove(&token_id);
                 } else {
                     // otherwise, update approvals_by_id with updated HashMap
-                    self.approvals_by_id.as_mut().unwrap().insert(&token_id, &approved_account_ids);
+                    approvals_by_id.insert(&token_id, &approved_account_ids);
                 }
             }
         }
@@ -116,22 +122,23 @@ impl NonFungibleTokenApproval for NonFungibleToken {
     fn nft_revoke_all(&mut self, token_id: TokenId) {
         assert_one_yocto();
         if self.approvals_by_id.is_none() {
-            env::panic(b"NFT does not support Approval Management");
+            ContractError::ApprovalNotSupported.panic()
         }
 
-        let owner_id = self.owner_by_id.get(&token_id).expect("Token not found");
+        let owner_id = self.owner_by_id.get(&token_id).unwrap_or_else(|| ContractError::TokenNotFound.panic());
         let predecessor_account_id = env::predecessor_account_id();
 
-        assert_eq!(&predecessor_account_id, &owner_id, "Predecessor must be token owner.");
+        require(predecessor_account_id == owner_id, ContractError::NotTokenOwner);
 
+        let approvals_by_id = self.approvals_by_id
+            .as_mut()
+            .unwrap_or_else(|| ContractError::ApprovalNotSupported.panic());
         // if token has no approvals, do nothing
-        if let Some(approved_account_ids) =
-            &mut self.approvals_by_id.as_mut().unwrap().get(&token_id)
-        {
+        if let Some(approved_account_ids) = &mut approvals_by_id.get(&token_id) {
             // otherwise, refund owner for storage costs of all approvals...
             refund_approved_account_ids(predecessor_account_id, &approved_account_ids);
             // ...and remove whole HashMap of approvals
-            self.approvals_by_id.as_mut().unwrap().remove(&token_id);
+            approvals_by_id.remove(&token_id);
         }
     }
 
@@ -141,31 +148,21 @@ impl NonFungibleTokenApproval for NonFungibleToken {
         approved_account_id: ValidAccountId,
         approval_id: Option<u64>,
     ) -> bool {
-        self.owner_by_id.get(&token_id).expect("Token not found");
+        self.owner_by_id.get(&token_id).unwrap_or_else(|| ContractError::TokenNotFound.panic());
 
-        if self.approvals_by_id.is_none() {
-            // contract does not support approval management
-            return false;
-        }
-
-        let approved_account_ids = self.approvals_by_id.unwrap().get(&token_id);
-        if approved_account_ids.is_none() {
-            // token has no approvals
-            return false;
-        }
+        // contract does not support approval management, or token has no approvals
+        let approved_account_ids = match self.approvals_by_id.and_then(|by_id| by_id.get(&token_id)) {
+            Some(approved_account_ids) => approved_account_ids,
+            None => return false,
+        };
 
         let account_id: AccountId = approved_account_id.into();
-        let actual_approval_id = approved_account_ids.as_ref().unwrap().get(&account_id);
-        if actual_approval_id.is_none() {
+        match (approved_account_ids.get(&account_id), approval_id) {
             // account not in approvals HashMap
-            return false;
-        }
-
-        if let Some(given_approval_id) = approval_id {
-            &given_approval_id == actual_approval_id.unwrap()
-        } else {
+            (None, _) => false,
+            (Some(actual_approval_id), Some(given_approval_id)) => *actual_approval_id == given_approval_id,
             // account approved, no approval_id given
-            true
+            (Some(_), None) => true,
         }
     }
 }
\ No newline at end of file
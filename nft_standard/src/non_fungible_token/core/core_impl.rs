@@ -1,16 +1,18 @@
 use super::resolver::NonFungibleTokenResolver;
+use crate::error::{require, ContractError};
 use crate::non_fungible_token::core::NonFungibleTokenCore;
 use crate::non_fungible_token::metadata::TokenMetadata;
+use crate::non_fungible_token::royalty::assert_valid_royalty;
 use crate::non_fungible_token::events::{NftMint, NftTransfer};
 use crate::non_fungible_token::token::{Token, TokenId};
 use crate::non_fungible_token::utils::{
-    hash_account_id, refund_approved_account_ids
+    assert_one_yocto, hash_account_id, refund_approved_account_ids
 };
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, TreeMap, UnorderedSet};
 use near_sdk::json_types::{Base64VecU8, ValidAccountId};
 use near_sdk::{
-    assert_one_yocto, env, ext_contract, log, AccountId, Balance, BorshStorageKey, CryptoHash, Gas,
+    env, ext_contract, log, AccountId, Balance, BorshStorageKey, CryptoHash, Gas,
     IntoStorageKey, PromiseOrValue, PromiseResult, StorageUsage,
 };
 use std::collections::HashMap;
@@ -73,8 +75,9 @@ pub struct NonFungibleToken {
     pub approvals_by_id: Option<LookupMap<TokenId, HashMap<AccountId, u64>>>,
     pub next_approval_id_by_id: Option<LookupMap<TokenId, u64>>,
 
-    //required by royalty extension
-    pub royalties_by_id: Option<LookupMap<TokenId, HashMap<AccountId, u128>>>
+    //required by royalty extension, holds only the tokens overriding the default royalty
+    pub royalties_by_id: Option<LookupMap<TokenId, HashMap<AccountId, u128>>>,
+    pub default_royalty: HashMap<AccountId, u128>
 }
 
 #[derive(BorshStorageKey, BorshSerialize)]
@@ -119,6 +122,7 @@ impl NonFungibleToken {
             approvals_by_id,
             next_approval_id_by_id,
             royalties_by_id: royalties_prefix.map(LookupMap::new),
+            default_royalty: HashMap::new(),
         };
         this.measure_min_token_storage_cost();
         this
@@ -212,10 +216,10 @@ impl NonFungibleToken {
 
         // if using Enumeration standard, update old & new owner's token lists
         if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
-            // owner_tokens should always exist, so call `unwrap` without guard
+            // owner_tokens should always exist
             let mut owner_tokens = tokens_per_owner
                 .get(from)
-                .expect("Unable to access tokens per owner in unguarded call.");
+                .unwrap_or_else(|| ContractError::MissingState("the tokens of the owner").panic());
             owner_tokens.remove(&token_id);
             if owner_tokens.is_empty() {
                 tokens_per_owner.remove(from);
@@ -262,7 +266,7 @@ impl NonFungibleToken {
         approval_id: Option<u64>,
         memo: Option<String>,
     ) -> (AccountId, Option<HashMap<AccountId, u64>>) {
-        let owner_id = self.owner_by_id.get(token_id).expect("Token not found");
+        let owner_id = self.owner_by_id.get(token_id).unwrap_or_else(|| ContractError::TokenNotFound.panic());
 
         // clear approvals, if using Approval Management extension
         // this will be rolled back by a panic if sending fails
@@ -272,30 +276,25 @@ impl NonFungibleToken {
         // check if authorized
         if sender_id != &owner_id {
             // if approval extension is NOT being used, or if token has no approved accounts
-            if approved_account_ids.is_none() {
-                env::panic(b"Unauthorized")
-            }
+            let approved_account_ids =
+                approved_account_ids.as_ref().unwrap_or_else(|| ContractError::Unauthorized.panic());
 
             // Approval extension is being used; get approval_id for sender.
-            let actual_approval_id = approved_account_ids.as_ref().unwrap().get(sender_id);
-
             // Panic if sender not approved at all
-            if actual_approval_id.is_none() {
-                env::panic(b"Sender not approved");
-            }
+            let actual_approval_id = *approved_account_ids
+                .get(sender_id)
+                .unwrap_or_else(|| ContractError::SenderNotApproved.panic());
 
             // If approval_id included, check that it matches
             if let Some(enforced_approval_id) = approval_id {
-                let actual_approval_id = actual_approval_id.unwrap();
-                assert_eq!(
-                    actual_approval_id, &enforced_approval_id,
-                    "The actual approval_id {} is different from the given approval_id {}",
-                    actual_approval_id, enforced_approval_id,
+                require(
+                    actual_approval_id == enforced_approval_id,
+                    ContractError::ApprovalIdMismatch { actual: actual_approval_id, given: enforced_approval_id },
                 );
             }
         }
 
-        assert_ne!(&owner_id, receiver_id, "Current and next owner must differ");
+        require(&owner_id != receiver_id, ContractError::SameOwner);
 
         self.internal_transfer_unguarded(&token_id, &owner_id, &receiver_id);
 
@@ -314,13 +313,14 @@ impl NonFungibleToken {
         token_owner_id: ValidAccountId,
         token_metadata: Option<TokenMetadata>,
         mint_cost: u128,
-        perpetual_royalties: HashMap<AccountId, u128>,
+        royalty: Option<HashMap<AccountId, u128>>,
+        memo: Option<String>,
     ) -> Token {
         if self.token_metadata_by_id.is_some() && token_metadata.is_none() {
-            env::panic(b"Must provide metadata");
+            ContractError::MetadataRequired.panic()
         }
         if self.owner_by_id.get(&token_id).is_some() {
-            env::panic(b"token_id must be unique");
+            ContractError::TokenAlreadyExists.panic()
         }
 
         let owner_id: AccountId = token_owner_id.clone().into();
@@ -331,9 +331,9 @@ impl NonFungibleToken {
         // Metadata extension: Save metadata, keep variable around to return later.
         // Note that check above already panicked if metadata extension in use but no metadata
         // provided to call.
-        self.token_metadata_by_id
-            .as_mut()
-            .and_then(|by_id| by_id.insert(&token_id, &token_metadata.as_ref().unwrap()));
+        if let (Some(by_id), Some(token_metadata)) = (&mut self.token_metadata_by_id, &token_metadata) {
+            by_id.insert(&token_id, token_metadata);
+        }
 
         // Enumeration extension: Record tokens_per_owner for use with enumeration view methods.
         if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
@@ -350,22 +350,30 @@ impl NonFungibleToken {
         let approved_account_ids =
             if self.approvals_by_id.is_some() { Some(HashMap::new()) } else { None };
 
-        // Royalty Management extension
-        let mut royalty = HashMap::<AccountId, u128>::new();
-        if let Some(royalties_by_id) = &mut self.royalties_by_id {
-            //make sure that the length of the perpetual royalties is below 7 since we won't have enough GAS to pay out that many people
-            assert!(perpetual_royalties.len() < 7, "Cannot add more than 6 perpetual royalty amounts");
-
-            //iterate through the perpetual royalties and insert the account and amount in the royalty map
-            for (account, amount) in perpetual_royalties {
-                royalty.insert(account, amount);
-            }
-            royalties_by_id.insert(&token_id, &royalty);
+        // Royalty Management extension: only tokens overriding the default royalty are stored
+        if let (Some(royalties_by_id), Some(royalty)) = (&mut self.royalties_by_id, &royalty) {
+            assert_valid_royalty(royalty);
+            royalties_by_id.insert(&token_id, royalty);
         }
+        let royalty = self.royalties_by_id.as_ref().map(|_| self.token_royalty(&token_id));
 
         // Return any extra attached deposit not used for storage
-        NftMint { owner_id: &token_owner_id.to_string(), token_ids: &[&token_id], memo: None }.emit();
-        Token { token_id, owner_id, metadata: token_metadata, approved_account_ids, royalty: Some(royalty) }
+        NftMint { owner_id: &token_owner_id.to_string(), token_ids: &[&token_id], memo: memo.as_deref() }.emit();
+        Token { token_id, owner_id, metadata: token_metadata, approved_account_ids, royalty }
+    }
+
+    /// Sets the royalty of every token without an override of its own.
+    pub fn set_default_royalty(&mut self, royalty: HashMap<AccountId, u128>) {
+        assert_valid_royalty(&royalty);
+        self.default_royalty = royalty;
+    }
+
+    /// Royalty paid out for `token_id`: its override if it has one, the default royalty otherwise.
+    pub fn token_royalty(&self, token_id: &TokenId) -> HashMap<AccountId, u128> {
+        self.royalties_by_id
+            .as_ref()
+            .and_then(|by_id| by_id.get(token_id))
+            .unwrap_or_else(|| self.default_royalty.clone())
     }
 
 }
@@ -419,13 +427,12 @@ impl NonFungibleTokenCore for NonFungibleToken {
 
     fn nft_token(self, token_id: TokenId) -> Option<Token> {
         let owner_id = self.owner_by_id.get(&token_id)?;
+        // the royalty paid out, default royalty included, without storing it per token
+        let royalty = self.royalties_by_id.as_ref().map(|_| self.token_royalty(&token_id));
         let metadata = self.token_metadata_by_id.and_then(|by_id| by_id.get(&token_id));
         let approved_account_ids = self
             .approvals_by_id
             .and_then(|by_id| by_id.get(&token_id).or_else(|| Some(HashMap::new())));
-        let royalty = self
-            .royalties_by_id
-            .and_then(|by_id| by_id.get(&token_id).or_else(|| Some(HashMap::new())));
         Some(Token { token_id, owner_id, metadata, approved_account_ids, royalty: royalty })
     }
 
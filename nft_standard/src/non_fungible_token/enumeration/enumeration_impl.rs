@@ -1,9 +1,9 @@
 use super::NonFungibleTokenEnumeration;
+use crate::error::{require, ContractError};
 use crate::non_fungible_token::token::Token;
 use crate::non_fungible_token::NonFungibleToken;
 use near_sdk::json_types::{ValidAccountId, U128};
 use near_sdk::AccountId;
-use std::collections::HashMap;
 
 type TokenId = String;
 
@@ -11,12 +11,10 @@ impl NonFungibleToken {
     /// Helper function used by a enumerations methods
     /// Note: this method is not exposed publicly to end users
     fn enum_get_token(&self, owner_id: AccountId, token_id: TokenId) -> Token {
-        let metadata = self.token_metadata_by_id.as_ref().unwrap().get(&token_id);
+        let metadata = self.token_metadata_by_id.as_ref().and_then(|by_id| by_id.get(&token_id));
         let approved_account_ids =
-            Some(self.approvals_by_id.as_ref().unwrap().get(&token_id).unwrap_or_default());
-        let royalty = self
-            .royalties_by_id.as_ref()
-            .and_then(|by_id| by_id.get(&token_id).or_else(|| Some(HashMap::new())));
+            self.approvals_by_id.as_ref().map(|by_id| by_id.get(&token_id).unwrap_or_default());
+        let royalty = self.royalties_by_id.as_ref().map(|_| self.token_royalty(&token_id));
 
         Token { token_id, owner_id, metadata, approved_account_ids, royalty }
     }
@@ -33,12 +31,9 @@ impl NonFungibleTokenEnumeration for NonFungibleToken {
         // Defaults to 0 based on the spec:
         // https://nomicon.io/Standards/NonFungibleToken/Enumeration.html#interface
         let start_index: u128 = from_index.map(From::from).unwrap_or_default();
-        assert!(
-            (self.owner_by_id.len() as u128) > start_index,
-            "Out of bounds, please use a smaller from_index."
-        );
+        require((self.owner_by_id.len() as u128) > start_index, ContractError::FromIndexOutOfBounds);
         let limit = limit.map(|v| v as usize).unwrap_or(usize::MAX);
-        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        require(limit != 0, ContractError::ZeroLimit);
         self.owner_by_id
             .iter()
             .skip(start_index as usize)
@@ -48,9 +43,8 @@ impl NonFungibleTokenEnumeration for NonFungibleToken {
     }
 
     fn nft_supply_for_owner(self, account_id: ValidAccountId) -> U128 {
-        let tokens_per_owner = self.tokens_per_owner.expect(
-            "Could not find tokens_per_owner when calling a method on the enumeration standard.",
-        );
+        let tokens_per_owner =
+            self.tokens_per_owner.unwrap_or_else(|| ContractError::EnumerationNotSupported.panic());
         tokens_per_owner
             .get(account_id.as_ref())
             .map(|account_tokens| U128::from(account_tokens.len() as u128))
@@ -63,21 +57,17 @@ impl NonFungibleTokenEnumeration for NonFungibleToken {
         from_index: Option<U128>,
         limit: Option<u64>,
     ) -> Vec<Token> {
-        let tokens_per_owner = self.tokens_per_owner.as_ref().expect(
-            "Could not find tokens_per_owner when calling a method on the enumeration standard.",
-        );
+        let tokens_per_owner =
+            self.tokens_per_owner.as_ref().unwrap_or_else(|| ContractError::EnumerationNotSupported.panic());
         let token_set = if let Some(token_set) = tokens_per_owner.get(account_id.as_ref()) {
             token_set
         } else {
             return vec![];
         };
         let limit = limit.map(|v| v as usize).unwrap_or(usize::MAX);
-        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        require(limit != 0, ContractError::ZeroLimit);
         let start_index: u128 = from_index.map(From::from).unwrap_or_default();
-        assert!(
-            token_set.len() as u128 > start_index,
-            "Out of bounds, please use a smaller from_index."
-        );
+        require(token_set.len() as u128 > start_index, ContractError::FromIndexOutOfBounds);
         token_set
             .iter()
             .skip(start_index as usize)
@@ -7,11 +7,12 @@
 //! This is an extension of the events format (nep-297):
 //! <https://github.com/near/NEPs/blob/master/specs/Standards/EventsFormat.md>
 //!
-//! The three events in this standard are [`NftMint`], [`NftTransfer`], and [`NftBurn`].
+//! The events in this standard are [`NftMint`], [`NftTransfer`], [`NftBurn`] and, since
+//! version 1.1.0, [`NftMetadataUpdate`].
 //!
 //! These events can be logged by calling `.emit()` on them if a single event, or calling
-//! [`NftMint::emit_many`], [`NftTransfer::emit_many`],
-//! or [`NftBurn::emit_many`] respectively.
+//! [`NftMint::emit_many`], [`NftTransfer::emit_many`], [`NftBurn::emit_many`]
+//! or [`NftMetadataUpdate::emit_many`] respectively.
 
 use crate::event::NearEvent;
 use near_sdk::AccountId;
@@ -95,6 +96,30 @@ impl NftBurn<'_> {
     }
 }
 
+/// Data to log when the metadata of existing tokens changed. To log this event,
+/// call [`.emit()`](NftMetadataUpdate::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct NftMetadataUpdate<'a> {
+    pub token_ids: &'a [&'a str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+}
+
+impl NftMetadataUpdate<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    /// Emits an nft metadata update event, through [`env::log_str`](near_sdk::env::log_str),
+    /// where each [`NftMetadataUpdate`] represents the data of each update.
+    pub fn emit_many<'a>(data: &'a [NftMetadataUpdate<'a>]) {
+        new_171_v1_1(Nep171EventKind::NftMetadataUpdate(data)).emit()
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub(crate) struct Nep171Event<'a> {
     version: &'static str,
@@ -110,6 +135,7 @@ enum Nep171EventKind<'a> {
     NftMint(&'a [NftMint<'a>]),
     NftTransfer(&'a [NftTransfer<'a>]),
     NftBurn(&'a [NftBurn<'a>]),
+    NftMetadataUpdate(&'a [NftMetadataUpdate<'a>]),
 }
 
 fn new_171<'a>(version: &'static str, event_kind: Nep171EventKind<'a>) -> NearEvent<'a> {
@@ -120,6 +146,10 @@ fn new_171_v1(event_kind: Nep171EventKind) -> NearEvent {
     new_171("1.0.0", event_kind)
 }
 
+fn new_171_v1_1(event_kind: Nep171EventKind) -> NearEvent {
+    new_171("1.1.0", event_kind)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,4 +252,14 @@ mod tests {
             r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","token_ids":["2","3"],"authorized_id":"bob","memo":"has memo"},{"old_owner_id":"bob","new_owner_id":"alice","token_ids":["0","1"]}]}"#
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn nft_metadata_update() {
+        let token_ids = &["0", "1"];
+        NftMetadataUpdate { token_ids, memo: Some("reveal") }.emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.1.0","event":"nft_metadata_update","data":[{"token_ids":["0","1"],"memo":"reveal"}]}"#
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::{require, ContractError};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::json_types::Base64VecU8;
 use near_sdk::serde::{Deserialize, Serialize};
@@ -46,24 +47,33 @@ pub trait NonFungibleTokenMetadataProvider {
 
 impl NFTContractMetadata {
     pub fn assert_valid(&self) {
-        assert_eq!(&self.spec, NFT_METADATA_SPEC);
-        assert_eq!(self.reference.is_some(), self.reference_hash.is_some());
+        require(self.spec == NFT_METADATA_SPEC, ContractError::InvalidMetadata("unsupported spec"));
+        require(
+            self.reference.is_some() == self.reference_hash.is_some(),
+            ContractError::InvalidMetadata("reference and reference_hash must be set together"),
+        );
         if let Some(reference_hash) = &self.reference_hash {
-            assert_eq!(reference_hash.0.len(), 32, "Hash has to be 32 bytes");
+            require(reference_hash.0.len() == 32, ContractError::InvalidMetadata("hash has to be 32 bytes"));
         }
     }
 }
 
 impl TokenMetadata {
     pub fn assert_valid(&self) {
-        assert_eq!(self.media.is_some(), self.media_hash.is_some());
+        require(
+            self.media.is_some() == self.media_hash.is_some(),
+            ContractError::InvalidMetadata("media and media_hash must be set together"),
+        );
         if let Some(media_hash) = &self.media_hash {
-            assert_eq!(media_hash.0.len(), 32, "Media hash has to be 32 bytes");
+            require(media_hash.0.len() == 32, ContractError::InvalidMetadata("media hash has to be 32 bytes"));
         }
 
-        assert_eq!(self.reference.is_some(), self.reference_hash.is_some());
+        require(
+            self.reference.is_some() == self.reference_hash.is_some(),
+            ContractError::InvalidMetadata("reference and reference_hash must be set together"),
+        );
         if let Some(reference_hash) = &self.reference_hash {
-            assert_eq!(reference_hash.0.len(), 32, "Reference hash has to be 32 bytes");
+            require(reference_hash.0.len() == 32, ContractError::InvalidMetadata("reference hash has to be 32 bytes"));
         }
     }
 }
\ No newline at end of file
@@ -1,16 +1,14 @@
-use crate::*;
 use near_sdk::json_types::U128;
 use std::collections::HashMap;
 use near_sdk::AccountId;
 use crate::non_fungible_token::refund_approved_account_ids;
-use near_sdk::assert_one_yocto;
+use crate::non_fungible_token::utils::assert_one_yocto;
+use crate::error::{require, ContractError};
 use crate::non_fungible_token::TokenId;
 use crate::non_fungible_token::NonFungibleToken;
 use near_sdk::Balance;
 use serde::{Serialize, Deserialize};
-use near_sdk::near_bindgen;
 use near_sdk::env;
-use std::convert::TryInto;
 
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -18,8 +16,43 @@ pub struct Payout {
   pub payout: HashMap<AccountId, U128>,
 }
 
-pub(crate) fn royalty_to_payout(royalty_percentage: u32, amount_to_pay: Balance) -> U128 {
-    U128(royalty_percentage as u128 * amount_to_pay / 10_000u128)
+/// Royalty amounts are in basis points of the sale price.
+pub const ROYALTY_BASIS_POINTS: u128 = 10_000;
+/// Receivers a royalty can have, more would not fit in the gas of a payout.
+pub const MAX_ROYALTY_RECEIVERS: usize = 6;
+
+/// Panics unless `royalty` has at most `MAX_ROYALTY_RECEIVERS` receivers, each with an amount
+/// greater than 0, adding up to at most `ROYALTY_BASIS_POINTS`.
+pub fn assert_valid_royalty(royalty: &HashMap<AccountId, u128>) {
+    require(royalty.len() <= MAX_ROYALTY_RECEIVERS, ContractError::TooManyRoyalties);
+    require(royalty.values().all(|amount| *amount > 0), ContractError::InvalidRoyalty("amounts must be greater than 0"));
+    let total = royalty.values().fold(0u128, |total, amount| total.saturating_add(*amount));
+    require(total <= ROYALTY_BASIS_POINTS, ContractError::InvalidRoyalty("amounts cannot add up to more than 10000"));
+}
+
+//splits balance between the royalty receivers and the owner. The owner gets whatever the
+//receivers don't, rounding remainders included, so the payout always adds up to balance
+fn compute_payout(royalty: &HashMap<AccountId, u128>, owner_id: AccountId, balance: Balance, max_len_payout: u32) -> Payout {
+    assert_valid_royalty(royalty);
+    let mut payout = HashMap::new();
+    let mut paid: Balance = 0;
+    for (account_id, amount) in royalty.iter() {
+        //the owner is paid at the end
+        if *account_id == owner_id {
+            continue;
+        }
+        let share = balance
+            .checked_mul(*amount)
+            .unwrap_or_else(|| ContractError::PayoutOverflow.panic())
+            / ROYALTY_BASIS_POINTS;
+        paid += share;
+        payout.insert(account_id.clone(), U128(share));
+    }
+    payout.insert(owner_id, U128(balance - paid));
+
+    //make sure we're not paying out to too many people (GAS limits this)
+    require(payout.len() as u32 <= max_len_payout, ContractError::TooManyPayoutReceivers);
+    Payout { payout }
 }
 
 pub trait Royalty {
@@ -39,44 +72,12 @@ pub trait Royalty {
 }
 
 impl Royalty for NonFungibleToken {
-
     //calculates the payout for a token given the passed in balance. This is a view method
     fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: u32) -> Payout {
-
         //get the owner of the token
-        let owner_id = self.owner_by_id.get(&token_id).unwrap();
-        //keep track of the total perpetual royalties
-        let mut total_perpetual = 0;
-        //get the u128 version of the passed in balance (which was U128 before)
-        let balance_u128 = balance.0;
-		//keep track of the payout object to send back
-        let mut payout_object = Payout {
-            payout: HashMap::new()
-        };
-        //get the royalty object from token
-		let royalty = self.royalties_by_id.as_ref().unwrap().get(&token_id).unwrap();
-
-        //make sure we're not paying out to too many people (GAS limits this)
-		assert!(royalty.len() as u32 <= max_len_payout, "Market cannot payout to that many receivers");
-
-        //go through each key and value in the royalty object
-		for (k, v) in royalty.iter() {
-            //get the key
-			let key = k.clone();
-            //only insert into the payout if the key isn't the token owner (we add their payout at the end)
-			if key != owner_id {
-                //
-				payout_object.payout.insert(key, royalty_to_payout((*v).try_into().unwrap(), balance_u128));
-				total_perpetual += *v;
-			}
-		}
-
-		// payout to previous owner who gets 100% - total perpetual royalties
-		payout_object.payout.insert(owner_id, royalty_to_payout((10000 - total_perpetual).try_into().unwrap(), balance_u128));
-
-        //return the payout object
-		payout_object
-	}
+        let owner_id = self.owner_by_id.get(&token_id).unwrap_or_else(|| ContractError::TokenNotFound.panic());
+        compute_payout(&self.token_royalty(&token_id), owner_id, balance.0, max_len_payout)
+    }
 
     //transfers the token to the receiver ID and returns the payout object that should be payed given the passed in balance. 
     fn nft_transfer_payout(
@@ -92,8 +93,8 @@ impl Royalty for NonFungibleToken {
         assert_one_yocto();
         //get the sender ID
         let sender_id = env::predecessor_account_id();
-        //transfer the token to the passed in receiver and get the previous token object back
-        let previous_token = self.internal_transfer(
+        //transfer the token to the passed in receiver and get the previous owner and approvals back
+        let (previous_owner, previous_approved) = self.internal_transfer(
             &sender_id,
             &receiver_id,
             &token_id,
@@ -102,43 +103,40 @@ impl Royalty for NonFungibleToken {
         );
 
         //refund the previous token owner for the storage used up by the previous approved account IDs
-        let (previous_owner, previous_approved) = previous_token;
-        refund_approved_account_ids(
-            previous_owner.clone(), &previous_approved.unwrap()
-        );
+        if let Some(previous_approved) = previous_approved {
+            refund_approved_account_ids(previous_owner.clone(), &previous_approved);
+        }
 
-        //get the owner of the token
-        let owner_id = previous_owner;
-        //keep track of the total perpetual royalties
-        let mut total_perpetual: u128 = 0;
-        //get the u128 version of the passed in balance (which was U128 before)
-        let balance_u128 = balance.0;
-		//keep track of the payout object to send back
-        let mut payout_object = Payout {
-            payout: HashMap::new()
-        };
-        //get the royalty object from token
-		let royalty = self.royalties_by_id.as_ref().unwrap().get(&token_id).unwrap();
-
-        //make sure we're not paying out to too many people (GAS limits this)
-		assert!(royalty.len() as u32 <= max_len_payout, "Market cannot payout to that many receivers");
-
-        //go through each key and value in the royalty object
-		for (k, v) in royalty.iter() {
-            //get the key
-			let key = k.clone();
-            //only insert into the payout if the key isn't the token owner (we add their payout at the end)
-			if key != owner_id {
-                //
-				payout_object.payout.insert(key, royalty_to_payout((*v).try_into().unwrap(), balance_u128));
-				total_perpetual += *v;
-			}
-		}
-
-		// payout to previous owner who gets 100% - total perpetual royalties
-		payout_object.payout.insert(owner_id, royalty_to_payout((10000 - total_perpetual).try_into().unwrap(), balance_u128));
-
-        //return the payout object
-		payout_object
+        //the previous owner gets what the royalty receivers don't
+        compute_payout(&self.token_royalty(&token_id), previous_owner, balance.0, max_len_payout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(payout: &Payout) -> u128 {
+        payout.payout.values().map(|amount| amount.0).sum()
+    }
+
+    #[test]
+    fn owner_gets_rounding_remainder() {
+        let royalty = HashMap::from([("alice".to_string(), 1_000), ("bob".to_string(), 333)]);
+        let payout = compute_payout(&royalty, "carol".to_string(), 1_001, 10);
+
+        assert_eq!(payout.payout["alice"].0, 100);
+        assert_eq!(payout.payout["bob"].0, 33);
+        assert_eq!(payout.payout["carol"].0, 868);
+        assert_eq!(total(&payout), 1_001);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn owner_listed_as_receiver_is_paid_once() {
+        let royalty = HashMap::from([("alice".to_string(), 10_000)]);
+        let payout = compute_payout(&royalty, "alice".to_string(), 7, 10);
+
+        assert_eq!(payout.payout.len(), 1);
+        assert_eq!(total(&payout), 7);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::{require, ContractError};
 use near_sdk::{env, AccountId, Balance, CryptoHash, Promise};
 use std::collections::HashMap;
 use std::mem::size_of;
@@ -30,11 +31,7 @@ pub fn refund_deposit(storage_used: u64) {
     let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
     let attached_deposit = env::attached_deposit();
 
-    assert!(
-        required_cost <= attached_deposit,
-        "Must attach {} yoctoNEAR to cover storage",
-        required_cost,
-    );
+    require(required_cost <= attached_deposit, ContractError::InsufficientDeposit { required: required_cost });
 
     let refund = attached_deposit - required_cost;
     if refund > 1 {
@@ -46,10 +43,9 @@ pub fn refund_deposit_mint(storage_used: u64, mint_cost: u128) {
     let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
     let attached_deposit = env::attached_deposit();
 
-    assert!(
+    require(
         required_cost + mint_cost <= attached_deposit,
-        "Must attach {} yoctoNEAR to cover storage and mint cost",
-        required_cost + mint_cost,
+        ContractError::InsufficientDeposit { required: required_cost + mint_cost },
     );
 
     let refund = attached_deposit - mint_cost - required_cost;
@@ -66,5 +62,10 @@ pub fn hash_account_id(account_id: &AccountId) -> CryptoHash {
 
 /// Assert that at least 1 yoctoNEAR was attached.
 pub(crate) fn assert_at_least_one_yocto() {
-    assert!(env::attached_deposit() >= 1, "Requires attached deposit of at least 1 yoctoNEAR")
+    require(env::attached_deposit() >= 1, ContractError::RequiresAttachedDeposit)
+}
+
+/// Assert that exactly 1 yoctoNEAR was attached.
+pub fn assert_one_yocto() {
+    require(env::attached_deposit() == 1, ContractError::RequiresOneYocto)
 }
\ No newline at end of file